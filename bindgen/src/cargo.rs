@@ -0,0 +1,398 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! `--manifest-path` and `--package`: finding the cdylib of a package and the crates with
+//! UniFFI components in it through `cargo metadata`, instead of being handed the library.
+//!
+//! The bindings are then generated the way library mode generates them. Library mode runs `cargo
+//! metadata` again to find each crate's `uniffi.toml`, while this reuses the metadata we already
+//! have.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use cargo_metadata::{DependencyKind, Metadata as CargoMetadata, MetadataCommand, Package, Target};
+use fs_err as fs;
+use uniffi_bindgen::{
+    library_mode::{calc_cdylib_name, find_components},
+    macro_metadata::extract_from_library,
+    BindingGenerator, Component, GenerationSettings,
+};
+use uniffi_meta::Metadata;
+
+use crate::gen_php::cdylib_file_name;
+
+/// The cdylib a package builds, and what's in it.
+pub struct CargoLibrary {
+    pub metadata: CargoMetadata,
+    pub library_path: Utf8PathBuf,
+    /// The crates of the dependency graph with components in the library, the package's own
+    /// first.
+    pub crate_names: Vec<String>,
+}
+
+/// Run `cargo metadata` for the manifest, or the one in the current directory, and find the
+/// library `cargo build` leaves in the target directory for `package`, or the root package.
+pub fn locate(
+    manifest_path: Option<&Utf8Path>,
+    package: Option<&str>,
+    release: bool,
+) -> Result<CargoLibrary> {
+    let mut command = MetadataCommand::new();
+    if let Some(path) = manifest_path {
+        command.manifest_path(path);
+    }
+    let metadata = command.exec().context("error running cargo metadata")?;
+
+    let package = match package {
+        Some(name) => metadata
+            .packages
+            .iter()
+            .find(|p| p.name == name)
+            .with_context(|| format!("there's no package `{name}` in the dependency graph"))?,
+        None => metadata.root_package().with_context(|| {
+            format!(
+                "`{}` is a virtual manifest, choose a package with --package",
+                metadata.workspace_root.join("Cargo.toml")
+            )
+        })?,
+    };
+    let target = package
+        .targets
+        .iter()
+        .find(|t| t.kind.iter().any(|kind| kind == "cdylib"))
+        .with_context(|| {
+            format!(
+                "`{}` doesn't build a cdylib, add `crate-type = [\"cdylib\"]` to its `[lib]`",
+                package.name
+            )
+        })?;
+    let profile = if release { "release" } else { "debug" };
+    let library_path = metadata
+        .target_directory
+        .join(profile)
+        .join(cdylib_file_name(&crate_name(target)));
+    if !library_path.exists() {
+        bail!(
+            "`{library_path}` doesn't exist, build it with `cargo build -p {}{}`",
+            package.name,
+            if release { " --release" } else { "" }
+        );
+    }
+
+    let mut crate_names = uniffi_crates(&metadata, package)?;
+    // Depending on `uniffi` doesn't mean having components, so we only keep the crates the
+    // library has metadata for, the same way library mode groups it.
+    let in_library: HashSet<_> = extract_from_library(&library_path)?
+        .into_iter()
+        .filter_map(|item| match item {
+            Metadata::Namespace(namespace) => Some(namespace.crate_name),
+            Metadata::UdlFile(udl) => Some(udl.module_path),
+            _ => None,
+        })
+        .collect();
+    crate_names.retain(|name| in_library.contains(name));
+    if crate_names.is_empty() {
+        bail!("`{}` has no UniFFI components", package.name);
+    }
+
+    Ok(CargoLibrary {
+        metadata,
+        library_path,
+        crate_names,
+    })
+}
+
+/// The crates depending on `uniffi` which `package` is built from, itself first: those whose
+/// components can be in its library.
+fn uniffi_crates(metadata: &CargoMetadata, package: &Package) -> Result<Vec<String>> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .context("cargo metadata didn't resolve the dependencies")?;
+    let deps: HashMap<_, _> = resolve
+        .nodes
+        .iter()
+        .map(|node| (&node.id, &node.deps))
+        .collect();
+    let mut crate_names = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([&package.id]);
+    while let Some(id) = queue.pop_front() {
+        if !seen.insert(id) {
+            continue;
+        }
+        let package = &metadata[id];
+        if uses_uniffi(package) {
+            if let Some(target) = package.targets.iter().find(|t| is_lib(t)) {
+                crate_names.push(crate_name(target));
+            }
+        }
+        // Build scripts and tests don't end up in the library.
+        let normal_deps = deps.get(id).into_iter().copied().flatten().filter(|dep| {
+            dep.dep_kinds
+                .iter()
+                .any(|info| info.kind == DependencyKind::Normal)
+        });
+        queue.extend(normal_deps.map(|dep| &dep.pkg));
+    }
+    Ok(crate_names)
+}
+
+/// Generate the bindings of each of the library's components into `out_dir/<crate>`, or only
+/// those of `crate_name`. `config_override` is merged into each crate's `uniffi.toml`.
+pub fn generate_bindings<T: BindingGenerator>(
+    library: &CargoLibrary,
+    crate_name: Option<&str>,
+    binding_generator: &T,
+    config_override: Option<&Utf8Path>,
+    out_dir: &Utf8Path,
+    try_format_code: bool,
+) -> Result<()> {
+    let config_override = config_override
+        .map(|path| -> Result<toml::value::Table> {
+            toml::from_str(&fs::read_to_string(path)?)
+                .with_context(|| format!("failed to parse `{path}`"))
+        })
+        .transpose()?;
+    let mut components = find_components(&library.metadata, &library.library_path)?
+        .into_iter()
+        .map(|Component { ci, mut config }| {
+            if let Some(config_override) = &config_override {
+                merge_toml(&mut config, config_override.clone());
+            }
+            let config = binding_generator.new_config(&toml::Value::from(config))?;
+            Ok(Component { ci, config })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut settings = GenerationSettings {
+        out_dir: out_dir.to_owned(),
+        try_format_code,
+        cdylib: calc_cdylib_name(&library.library_path).map(str::to_owned),
+    };
+    // Every component of the library goes through this, even when only one is written, so
+    // they all share its `\FFI`.
+    binding_generator.update_component_configs(&settings, &mut components)?;
+    if let Some(crate_name) = crate_name {
+        components.retain(|c| c.ci.crate_name() == crate_name);
+    }
+    // Each crate gets a directory of its own, for its `composer.json` and the like.
+    for component in components {
+        settings.out_dir = out_dir.join(component.ci.crate_name());
+        fs::create_dir_all(&settings.out_dir)?;
+        binding_generator.write_bindings(&settings, &[component])?;
+    }
+    Ok(())
+}
+
+/// Upsert the keys of `b` into `a`, recursively.
+fn merge_toml(a: &mut toml::value::Table, b: toml::value::Table) {
+    for (key, value) in b {
+        match (a.get_mut(&key), value) {
+            (Some(toml::Value::Table(a)), toml::Value::Table(b)) => merge_toml(a, b),
+            (_, value) => {
+                a.insert(key, value);
+            }
+        }
+    }
+}
+
+fn uses_uniffi(package: &Package) -> bool {
+    package
+        .dependencies
+        .iter()
+        .any(|dep| dep.name == "uniffi" && dep.kind == DependencyKind::Normal)
+}
+
+fn is_lib(target: &Target) -> bool {
+    target.kind.iter().any(|kind| {
+        matches!(
+            kind.as_str(),
+            "lib" | "rlib" | "dylib" | "cdylib" | "staticlib"
+        )
+    })
+}
+
+/// The name the crate goes by in Rust, and in the components' metadata.
+fn crate_name(target: &Target) -> String {
+    target.name.replace('-', "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A workspace of crates depending on each other by path, so `cargo metadata` needs no
+    /// registry. `uniffi` is one of them, an empty crate.
+    struct Workspace(Utf8PathBuf);
+
+    impl Workspace {
+        fn new(name: &str) -> Self {
+            let root = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+                .unwrap()
+                .join(format!("uniffi-bindgen-php-{}-{name}", std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            let workspace = Self(root);
+            workspace.write(
+                "Cargo.toml",
+                r#"
+                [workspace]
+                members = ["app", "app-core", "plain", "deep", "testing", "codegen", "uniffi"]
+                "#,
+            );
+            workspace.package(
+                "app",
+                "crate-type = [\"cdylib\", \"lib\"]",
+                &[
+                    "[dependencies]",
+                    "uniffi = { path = \"../uniffi\" }",
+                    "app-core = { path = \"../app-core\" }",
+                    "plain = { path = \"../plain\" }",
+                    "[dev-dependencies]",
+                    "testing = { path = \"../testing\" }",
+                    "[build-dependencies]",
+                    "codegen = { path = \"../codegen\" }",
+                ],
+            );
+            for name in ["app-core", "deep", "testing", "codegen"] {
+                workspace.package(
+                    name,
+                    "",
+                    &["[dependencies]", "uniffi = { path = \"../uniffi\" }"],
+                );
+            }
+            workspace.package(
+                "plain",
+                "",
+                &["[dependencies]", "deep = { path = \"../deep\" }"],
+            );
+            workspace.package("uniffi", "", &[]);
+            workspace
+        }
+
+        fn write(&self, path: &str, contents: &str) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        fn package(&self, name: &str, lib: &str, dependencies: &[&str]) {
+            self.write(
+                &format!("{name}/Cargo.toml"),
+                &format!(
+                    "[package]\nname = \"{name}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[lib]\n{lib}\n\n{}\n",
+                    dependencies.join("\n")
+                ),
+            );
+            self.write(&format!("{name}/src/lib.rs"), "");
+        }
+
+        fn manifest(&self, package: &str) -> Utf8PathBuf {
+            self.0.join(package).join("Cargo.toml")
+        }
+
+        fn locate_error(&self, manifest: &str, package: Option<&str>, release: bool) -> String {
+            let manifest = self.0.join(manifest);
+            match locate(Some(&manifest), package, release) {
+                Ok(_) => panic!("located a library which was never built"),
+                Err(error) => error.to_string(),
+            }
+        }
+    }
+
+    impl Drop for Workspace {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn crates_with_components_come_from_normal_dependencies() {
+        let workspace = Workspace::new("crates");
+        let metadata = MetadataCommand::new()
+            .manifest_path(workspace.manifest("app"))
+            .exec()
+            .unwrap();
+        let app = metadata.packages.iter().find(|p| p.name == "app").unwrap();
+        // Not the `testing` dev-dependency or the `codegen` build-dependency, and `deep`
+        // through `plain`, which doesn't use `uniffi` itself.
+        assert_eq!(
+            uniffi_crates(&metadata, app).unwrap(),
+            ["app", "app_core", "deep"]
+        );
+    }
+
+    #[test]
+    fn locating_the_library() {
+        let workspace = Workspace::new("locate");
+        assert_eq!(
+            workspace.locate_error("Cargo.toml", None, false),
+            format!(
+                "`{}` is a virtual manifest, choose a package with --package",
+                workspace.0.join("Cargo.toml")
+            )
+        );
+        assert_eq!(
+            workspace.locate_error("Cargo.toml", Some("missing"), false),
+            "there's no package `missing` in the dependency graph"
+        );
+        assert_eq!(
+            workspace.locate_error("plain/Cargo.toml", None, false),
+            "`plain` doesn't build a cdylib, add `crate-type = [\"cdylib\"]` to its `[lib]`"
+        );
+        let library = workspace
+            .0
+            .join("target/debug")
+            .join(cdylib_file_name("app"));
+        assert_eq!(
+            workspace.locate_error("Cargo.toml", Some("app"), false),
+            format!("`{library}` doesn't exist, build it with `cargo build -p app`")
+        );
+        let library = workspace
+            .0
+            .join("target/release")
+            .join(cdylib_file_name("app"));
+        assert_eq!(
+            workspace.locate_error("app/Cargo.toml", None, true),
+            format!("`{library}` doesn't exist, build it with `cargo build -p app --release`")
+        );
+    }
+
+    #[test]
+    fn config_overrides_merge_into_tables() {
+        let mut config: toml::value::Table = toml::from_str(
+            r#"
+            [bindings.php]
+            namespace = "Acme"
+            exclude = ["Internal"]
+            [bindings.kotlin]
+            package_name = "acme"
+            "#,
+        )
+        .unwrap();
+        let config_override = toml::from_str(
+            r#"
+            [bindings.php]
+            namespace = "Acme\\Bindings"
+            layout = "psr4"
+            "#,
+        )
+        .unwrap();
+        merge_toml(&mut config, config_override);
+        let expected: toml::value::Table = toml::from_str(
+            r#"
+            [bindings.php]
+            namespace = "Acme\\Bindings"
+            exclude = ["Internal"]
+            layout = "psr4"
+            [bindings.kotlin]
+            package_name = "acme"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config, expected);
+    }
+}
@@ -0,0 +1,38 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi_bindgen::backend::{CodeType, Literal};
+
+use super::PhpCodeType;
+
+#[derive(Debug)]
+pub struct CallbackInterfaceCodeType {
+    id: String,
+}
+
+impl CallbackInterfaceCodeType {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl CodeType for CallbackInterfaceCodeType {
+    fn type_label(&self) -> String {
+        super::PHPCodeOracle.class_name(&self.id)
+    }
+
+    fn canonical_name(&self) -> String {
+        format!("CallbackInterface{}", self.type_label())
+    }
+
+    fn literal(&self, _literal: &Literal) -> String {
+        unreachable!("callback interfaces have no literals, see `unsupported::check_defaults`");
+    }
+
+    fn initialization_fn(&self) -> Option<String> {
+        Some(format!("{}::register", self.ffi_converter_name()))
+    }
+}
+
+impl PhpCodeType for CallbackInterfaceCodeType {}
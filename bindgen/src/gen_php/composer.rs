@@ -0,0 +1,239 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Generate a `composer.json` so the bindings can be shipped as a Composer package.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use camino::Utf8PathBuf;
+use serde::{Deserialize, Serialize};
+
+use super::{AsyncRuntime, Config};
+
+/// The `[bindings.php.composer]` table. When present a `composer.json` is written next to the bindings.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ComposerConfig {
+    /// The package name, e.g. `acme/loro`.
+    name: String,
+    description: Option<String>,
+    license: Option<String>,
+    /// The PHP version constraint, by default the configured `php_version` or later.
+    php: Option<String>,
+    /// The version constraint used for the packages in `external_packages`.
+    external_packages_version: Option<String>,
+    /// Any further requirements, which take precedence over the ones we add.
+    #[serde(default)]
+    require: BTreeMap<String, String>,
+}
+
+/// What Composer's autoloader needs to find the generated code.
+#[derive(Debug, Default)]
+pub struct Autoload {
+    /// Namespace prefixes mapped to the directories holding their classes.
+    pub psr4: BTreeMap<String, Utf8PathBuf>,
+    /// Files to include on every request.
+    pub files: Vec<Utf8PathBuf>,
+}
+
+#[derive(Serialize)]
+struct ComposerJson<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(rename = "type")]
+    type_: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    license: Option<&'a str>,
+    #[serde(serialize_with = "serialize_require")]
+    require: BTreeMap<String, String>,
+    autoload: AutoloadJson,
+}
+
+#[derive(Serialize)]
+struct AutoloadJson {
+    #[serde(rename = "psr-4", skip_serializing_if = "BTreeMap::is_empty")]
+    psr4: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    files: Vec<String>,
+}
+
+/// List `php` and the extensions before any packages, like `composer require` does.
+fn serialize_require<S: serde::Serializer>(
+    require: &BTreeMap<String, String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut entries: Vec<_> = require.iter().collect();
+    entries.sort_by_key(|(name, _)| (*name != "php", !name.starts_with("ext-")));
+    serializer.collect_map(entries)
+}
+
+pub fn composer_json(
+    composer: &ComposerConfig,
+    config: &Config,
+    autoload: Autoload,
+) -> Result<String> {
+    let mut require = BTreeMap::new();
+    require.insert(
+        "php".to_string(),
+        composer
+            .php
+            .clone()
+            .unwrap_or_else(|| format!(">={}", config.php_version().as_str())),
+    );
+    // The extension backend needs its own extension rather than `ext-ffi`.
+    let extension = if config.backend.is_extension() {
+        config.extension_name()?
+    } else {
+        "ffi".to_string()
+    };
+    require.insert(format!("ext-{extension}"), "*".to_string());
+    match config.async_runtime {
+        AsyncRuntime::Fiber => {}
+        AsyncRuntime::Amp => {
+            require.insert("amphp/amp".to_string(), "^3.0".to_string());
+        }
+        AsyncRuntime::React => {
+            require.insert("react/promise".to_string(), "^3.0".to_string());
+            require.insert("react/event-loop".to_string(), "^1.5".to_string());
+        }
+    }
+    // `external_packages` maps crate names to the packages holding their bindings.
+    let external_version = composer.external_packages_version.as_deref().unwrap_or("*");
+    for package in config.external_packages.values() {
+        require.insert(package.clone(), external_version.to_string());
+    }
+    require.extend(composer.require.clone());
+
+    let json = ComposerJson {
+        name: &composer.name,
+        description: composer.description.as_deref(),
+        type_: "library",
+        license: composer.license.as_deref(),
+        require,
+        autoload: AutoloadJson {
+            psr4: autoload
+                .psr4
+                .into_iter()
+                .map(|(prefix, dir)| (format!("{prefix}\\"), format!("{dir}/")))
+                .collect(),
+            files: autoload.files.iter().map(|f| f.to_string()).collect(),
+        },
+    };
+
+    // Composer itself indents with 4 spaces.
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    json.serialize(&mut serializer)?;
+    out.push(b'\n');
+    Ok(String::from_utf8(out)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn generate(toml: &str, autoload: Autoload) -> String {
+        let config: Config = toml::from_str(toml).unwrap();
+        composer_json(config.composer.as_ref().unwrap(), &config, autoload).unwrap()
+    }
+
+    #[test]
+    fn requirements() {
+        let json = generate(
+            r#"
+            async_runtime = "react"
+            external_packages = { other = "acme/other", base = "acme/base" }
+            [composer]
+            name = "acme/loro"
+            license = "MIT"
+            require = { "ext-json" = "*", "react/promise" = "^3.1" }
+            "#,
+            Autoload::default(),
+        );
+        // `php` and the extensions first, then the packages in order, ours overridden by the
+        // config's.
+        assert_eq!(
+            json,
+            r#"{
+    "name": "acme/loro",
+    "type": "library",
+    "license": "MIT",
+    "require": {
+        "php": ">=8.1",
+        "ext-ffi": "*",
+        "ext-json": "*",
+        "acme/base": "*",
+        "acme/other": "*",
+        "react/event-loop": "^1.5",
+        "react/promise": "^3.1"
+    },
+    "autoload": {}
+}
+"#
+        );
+    }
+
+    #[test]
+    fn extension_backend() {
+        let json = generate(
+            r#"
+            module_name = "loro"
+            backend = "extension"
+            [composer]
+            name = "acme/loro"
+            "#,
+            Autoload::default(),
+        );
+        assert!(json.contains(r#""ext-uniffi_loro": "*""#));
+        assert!(!json.contains("ext-ffi"));
+    }
+
+    #[test]
+    fn external_packages_version() {
+        let json = generate(
+            r#"
+            php_version = "7.4"
+            external_packages = { other = "acme/other" }
+            [composer]
+            name = "acme/loro"
+            php = "^7.4 || ^8.0"
+            external_packages_version = "^1.2"
+            "#,
+            Autoload::default(),
+        );
+        assert!(json.contains(r#""php": "^7.4 || ^8.0","#));
+        assert!(json.contains(r#""acme/other": "^1.2""#));
+    }
+
+    #[test]
+    fn autoload() {
+        let mut autoload = Autoload::default();
+        autoload
+            .psr4
+            .insert("Acme\\Loro".into(), "src/Acme/Loro".into());
+        autoload.psr4.insert("Uniffi".into(), "src/Uniffi".into());
+        autoload.files.push("src/Acme/Loro/functions.php".into());
+        let json = generate(
+            r#"
+            [composer]
+            name = "acme/loro"
+            "#,
+            autoload,
+        );
+        // Namespace prefixes end in a backslash, escaped in JSON.
+        assert!(json.contains(
+            r#"    "autoload": {
+        "psr-4": {
+            "Acme\\Loro\\": "src/Acme/Loro/",
+            "Uniffi\\": "src/Uniffi/"
+        },
+        "files": [
+            "src/Acme/Loro/functions.php"
+        ]
+    }"#
+        ));
+    }
+}
@@ -0,0 +1,144 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi_bindgen::backend::{CodeType, Literal};
+use uniffi_meta::Type;
+
+use super::PhpCodeType;
+
+#[derive(Debug)]
+pub struct OptionalCodeType {
+    inner: Type,
+}
+
+impl OptionalCodeType {
+    pub fn new(inner: Type) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for OptionalCodeType {
+    fn type_label(&self) -> String {
+        let inner = super::PHPCodeOracle.find(&self.inner).type_label();
+        if inner.starts_with('?') {
+            inner
+        } else {
+            format!("?{inner}")
+        }
+    }
+
+    fn canonical_name(&self) -> String {
+        format!(
+            "Optional{}",
+            super::PHPCodeOracle.find(&self.inner).canonical_name()
+        )
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        match literal {
+            Literal::None => "null".into(),
+            Literal::Some { inner } => super::PHPCodeOracle.find(&self.inner).literal(inner),
+            _ => unreachable!("{literal:?} isn't optional, see `unsupported::check_defaults`"),
+        }
+    }
+}
+
+impl PhpCodeType for OptionalCodeType {
+    fn doc_type_label(&self) -> String {
+        let inner = super::PHPCodeOracle.find(&self.inner).doc_type_label();
+        if inner.starts_with('?') {
+            inner
+        } else {
+            format!("?{inner}")
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SequenceCodeType {
+    inner: Type,
+}
+
+impl SequenceCodeType {
+    pub fn new(inner: Type) -> Self {
+        Self { inner }
+    }
+}
+
+impl CodeType for SequenceCodeType {
+    fn type_label(&self) -> String {
+        "array".into()
+    }
+
+    fn canonical_name(&self) -> String {
+        format!(
+            "Sequence{}",
+            super::PHPCodeOracle.find(&self.inner).canonical_name()
+        )
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptySequence => "[]".into(),
+            _ => unreachable!("{literal:?} isn't a sequence, see `unsupported::check_defaults`"),
+        }
+    }
+}
+
+impl PhpCodeType for SequenceCodeType {
+    fn doc_type_label(&self) -> String {
+        format!(
+            "list<{}>",
+            super::PHPCodeOracle.find(&self.inner).doc_type_label()
+        )
+    }
+}
+
+#[derive(Debug)]
+pub struct MapCodeType {
+    key: Type,
+    value: Type,
+}
+
+impl MapCodeType {
+    pub fn new(key: Type, value: Type) -> Self {
+        Self { key, value }
+    }
+}
+
+impl CodeType for MapCodeType {
+    fn type_label(&self) -> String {
+        "array".into()
+    }
+
+    fn canonical_name(&self) -> String {
+        format!(
+            "Map{}{}",
+            super::PHPCodeOracle.find(&self.key).canonical_name(),
+            super::PHPCodeOracle.find(&self.value).canonical_name()
+        )
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        match literal {
+            Literal::EmptyMap => "[]".into(),
+            _ => unreachable!("{literal:?} isn't a map, see `unsupported::check_defaults`"),
+        }
+    }
+}
+
+impl PhpCodeType for MapCodeType {
+    fn doc_type_label(&self) -> String {
+        let key = super::PHPCodeOracle.find(&self.key);
+        // PHP turns any other array key into an int or a string.
+        let key = match key.type_label().as_str() {
+            "int" | "string" => key.doc_type_label(),
+            _ => "array-key".into(),
+        };
+        format!(
+            "array<{key}, {}>",
+            super::PHPCodeOracle.find(&self.value).doc_type_label()
+        )
+    }
+}
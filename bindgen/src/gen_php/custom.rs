@@ -0,0 +1,41 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi_bindgen::backend::{CodeType, Literal};
+use uniffi_meta::Type;
+
+use super::PhpCodeType;
+
+#[derive(Debug)]
+pub struct CustomCodeType {
+    id: String,
+    builtin: Type,
+}
+
+impl CustomCodeType {
+    pub fn new(id: String, builtin: Type) -> Self {
+        Self { id, builtin }
+    }
+}
+
+impl CodeType for CustomCodeType {
+    // PHP has no type aliases, so custom types are exposed as their builtin type.
+    fn type_label(&self) -> String {
+        super::PHPCodeOracle.find(&self.builtin).type_label()
+    }
+
+    fn canonical_name(&self) -> String {
+        format!("Type{}", self.id)
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        super::PHPCodeOracle.find(&self.builtin).literal(literal)
+    }
+}
+
+impl PhpCodeType for CustomCodeType {
+    fn doc_type_label(&self) -> String {
+        super::PHPCodeOracle.find(&self.builtin).doc_type_label()
+    }
+}
@@ -0,0 +1,58 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi_bindgen::backend::{CodeType, Literal};
+
+use super::PhpCodeType;
+
+#[derive(Debug)]
+pub struct EnumCodeType {
+    id: String,
+    /// Whether this is a flat enum generated as a class of `int` constants, for PHP versions
+    /// without native enums.
+    is_int: bool,
+}
+
+impl EnumCodeType {
+    pub fn new(id: String, is_int: bool) -> Self {
+        Self { id, is_int }
+    }
+}
+
+impl CodeType for EnumCodeType {
+    fn type_label(&self) -> String {
+        if self.is_int {
+            "int".into()
+        } else {
+            super::PHPCodeOracle.class_name(&self.id)
+        }
+    }
+
+    fn canonical_name(&self) -> String {
+        format!("Type{}", self.id)
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        if let Literal::Enum(v, _) = literal {
+            format!(
+                "{}::{}",
+                super::PHPCodeOracle.class_name(&self.id),
+                super::PHPCodeOracle.enum_variant_name(v, &self.id)
+            )
+        } else {
+            unreachable!("{literal:?} isn't a variant, see `unsupported::check_defaults`");
+        }
+    }
+}
+
+impl PhpCodeType for EnumCodeType {
+    // One of the class's constants.
+    fn doc_type_label(&self) -> String {
+        if self.is_int {
+            format!("{}::*", super::PHPCodeOracle.class_name(&self.id))
+        } else {
+            self.type_label()
+        }
+    }
+}
@@ -0,0 +1,293 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `extension` backend: the C source of a PHP extension which links the Rust library
+//! statically, for when `ext-ffi` isn't available.
+//!
+//! The extension declares one class with a method for each FFI function, which the bindings
+//! call instead of `\FFI`. Values are converted on the way in and out, so PHP only ever sees
+//! ints, floats and strings: pointers to Rust objects are ints, `RustBuffer`s are strings, and
+//! the `RustCallStatus` is written to a `UniffiRustCallStatus` object.
+//!
+//! Calls only go from PHP into Rust: the extension can't call back into PHP, so it has no
+//! callback interfaces, trait interfaces PHP can implement or async functions (which need PHP to
+//! be woken). `check_supported` refuses components with any of those rather than leaving them
+//! out, the `ffi` backend is the one to use for those.
+
+use std::{collections::HashSet, fmt::Write};
+
+use anyhow::{bail, Context, Result};
+use askama::Template;
+use camino::Utf8PathBuf;
+use uniffi_bindgen::{
+    interface::{Callable, FfiFunction, FfiType},
+    ComponentInterface,
+};
+
+use super::{Config, PHPCodeOracle};
+
+/// The class the extension declares in the component's namespace.
+pub const NATIVE_CLASS: &str = "UniffiNative";
+
+/// Fail if `ci` needs Rust to call into PHP, listing everything that does.
+pub fn check_supported(ci: &ComponentInterface) -> Result<()> {
+    let mut needs_php = Vec::new();
+    for cbi in ci.callback_interface_definitions() {
+        needs_php.push(format!("`{}` is a callback interface", cbi.name()));
+    }
+    for obj in ci.object_definitions() {
+        if obj.has_callback_interface() {
+            needs_php.push(format!("`{}` can be implemented in PHP", obj.name()));
+        }
+        for cons in obj.constructors() {
+            if cons.is_async() {
+                needs_php.push(format!("`{}.{}` is async", obj.name(), cons.name()));
+            }
+        }
+        for meth in obj.methods() {
+            if meth.is_async() {
+                needs_php.push(format!("`{}.{}` is async", obj.name(), meth.name()));
+            }
+        }
+    }
+    for func in ci.function_definitions() {
+        if func.is_async() {
+            needs_php.push(format!("`{}` is async", func.name()));
+        }
+    }
+    if !needs_php.is_empty() {
+        bail!(
+            "the `extension` backend can't generate `{}`, it only calls from PHP into Rust:\n{}\nUse the `ffi` backend for components which need Rust to call into PHP",
+            ci.namespace(),
+            needs_php
+                .iter()
+                .map(|reason| format!("    {reason}\n"))
+                .collect::<String>()
+                .trim_end()
+        );
+    }
+    Ok(())
+}
+
+/// The extension's sources, in `ext/` under the output directory.
+pub fn files(config: &Config, ci: &ComponentInterface) -> Result<Vec<(Utf8PathBuf, String)>> {
+    let extension_name = config.extension_name()?;
+    let extension = Extension {
+        ci,
+        extension_name: extension_name.clone(),
+        option_name: extension_name.replace('_', "-"),
+        constant_prefix: extension_name.to_uppercase(),
+        class_namespace: config.namespace()?.replace('\\', "\\\\"),
+        library_name: config.cdylib_name(),
+        version: env!("CARGO_PKG_VERSION"),
+        ffi_definitions: config.ffi_definitions(ci)?,
+        methods: native_functions(ci)
+            .iter()
+            .map(NativeMethod::new)
+            .collect::<Result<_>>()?,
+    };
+    let dir = Utf8PathBuf::from("ext");
+    Ok(vec![
+        (
+            dir.join("config.m4"),
+            ConfigM4 {
+                extension: &extension,
+            }
+            .render()
+            .context("failed to render config.m4")?,
+        ),
+        (
+            dir.join(format!("php_{extension_name}.h")),
+            ExtensionHeader {
+                extension: &extension,
+            }
+            .render()
+            .context("failed to render extension header")?,
+        ),
+        (
+            dir.join(format!("{extension_name}.c")),
+            extension
+                .render()
+                .context("failed to render extension source")?,
+        ),
+    ])
+}
+
+/// The FFI functions the bindings call, which are all of them but those driving Rust futures and
+/// `rustbuffer_from_bytes`: there are no async functions, and PHP strings are copied into
+/// `RustBuffer`s by the extension itself.
+fn native_functions(ci: &ComponentInterface) -> Vec<FfiFunction> {
+    let futures: HashSet<_> = ci
+        .iter_futures_ffi_function_definitions()
+        .map(|func| func.name().to_string())
+        .collect();
+    let from_bytes = ci.ffi_rustbuffer_from_bytes();
+    ci.iter_ffi_function_definitions()
+        .filter(|func| !futures.contains(func.name()) && func.name() != from_bytes.name())
+        .collect()
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "ext/extension.c")]
+struct Extension<'a> {
+    ci: &'a ComponentInterface,
+    extension_name: String,
+    /// The extension name for `./configure --with-...`.
+    option_name: String,
+    /// The extension name for macros: `UNIFFI_FOO`.
+    constant_prefix: String,
+    /// The PHP namespace, escaped for a C string.
+    class_namespace: String,
+    library_name: String,
+    version: &'static str,
+    ffi_definitions: String,
+    methods: Vec<NativeMethod>,
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "ext/config.m4")]
+struct ConfigM4<'a> {
+    extension: &'a Extension<'a>,
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "ext/php_extension.h")]
+struct ExtensionHeader<'a> {
+    extension: &'a Extension<'a>,
+}
+
+/// How a value of an `FfiType` crosses into PHP.
+#[derive(Clone, Copy)]
+enum NativeValue {
+    Long,
+    Double,
+    Pointer,
+    Buffer,
+}
+
+impl NativeValue {
+    /// Fails for anything but numbers, pointers and `RustBuffer`s, naming the FFI function
+    /// `func` which uses it.
+    fn new(ffi_type: &FfiType, func: &FfiFunction) -> Result<Self> {
+        Ok(match ffi_type {
+            FfiType::Int8
+            | FfiType::UInt8
+            | FfiType::Int16
+            | FfiType::UInt16
+            | FfiType::Int32
+            | FfiType::UInt32
+            | FfiType::Int64
+            | FfiType::UInt64
+            | FfiType::Handle => NativeValue::Long,
+            FfiType::Float32 | FfiType::Float64 => NativeValue::Double,
+            FfiType::RustArcPtr(_) => NativeValue::Pointer,
+            FfiType::RustBuffer(_) => NativeValue::Buffer,
+            _ => bail!(
+                "the `extension` backend can't pass `{ffi_type:?}` between PHP and Rust, which `{}` uses",
+                func.name()
+            ),
+        })
+    }
+}
+
+/// A method of the native class, calling one FFI function.
+struct NativeMethod {
+    name: String,
+    arg_names: Vec<String>,
+    body: String,
+}
+
+impl NativeMethod {
+    fn new(func: &FfiFunction) -> Result<Self> {
+        let args = func
+            .arguments()
+            .into_iter()
+            .map(|arg| {
+                let type_ = arg.type_();
+                Ok((
+                    format!("arg_{}", arg.name()),
+                    type_.clone(),
+                    NativeValue::new(&type_, func)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let ret = match func.return_type() {
+            Some(type_) => Some((type_.clone(), NativeValue::new(type_, func)?)),
+            None => None,
+        };
+
+        let mut arg_names: Vec<_> = args.iter().map(|(name, ..)| name.clone()).collect();
+        let mut body = String::new();
+        for (name, _, value) in &args {
+            let decl = match value {
+                NativeValue::Long | NativeValue::Pointer => format!("zend_long {name} = 0;"),
+                NativeValue::Double => format!("double {name} = 0;"),
+                NativeValue::Buffer => format!("zend_string *{name} = NULL;"),
+            };
+            writeln!(body, "    {decl}").unwrap();
+        }
+        if func.has_rust_call_status_arg() {
+            arg_names.push("uniffi_status".into());
+            body.push_str("    zval *uniffi_status = NULL;\n");
+            body.push_str("    RustCallStatus status = {0};\n");
+        }
+
+        let count = arg_names.len();
+        writeln!(body, "\n    ZEND_PARSE_PARAMETERS_START({count}, {count})").unwrap();
+        for (name, _, value) in &args {
+            let param = match value {
+                NativeValue::Long | NativeValue::Pointer => "Z_PARAM_LONG",
+                NativeValue::Double => "Z_PARAM_DOUBLE",
+                NativeValue::Buffer => "Z_PARAM_STR",
+            };
+            writeln!(body, "        {param}({name})").unwrap();
+        }
+        if func.has_rust_call_status_arg() {
+            body.push_str("        Z_PARAM_OBJECT(uniffi_status)\n");
+        }
+        body.push_str("    ZEND_PARSE_PARAMETERS_END();\n\n");
+
+        let mut call_args: Vec<_> = args
+            .iter()
+            .map(|(name, type_, value)| match value {
+                NativeValue::Long | NativeValue::Double => {
+                    format!("({}) {name}", PHPCodeOracle.ffi_type_label(type_))
+                }
+                NativeValue::Pointer => format!("(void *) (uintptr_t) {name}"),
+                NativeValue::Buffer => format!("uniffi_lower_buffer({name})"),
+            })
+            .collect();
+        if func.has_rust_call_status_arg() {
+            call_args.push("&status".into());
+        }
+        let call = format!("{}({})", func.name(), call_args.join(", "));
+        match &ret {
+            Some((type_, _)) => writeln!(
+                body,
+                "    {} result = {call};",
+                PHPCodeOracle.ffi_type_label(type_)
+            )
+            .unwrap(),
+            None => writeln!(body, "    {call};").unwrap(),
+        }
+        if func.has_rust_call_status_arg() {
+            body.push_str("    uniffi_write_status(uniffi_status, &status);\n");
+        }
+        if let Some((_, value)) = ret {
+            let ret = match value {
+                NativeValue::Long => "RETURN_LONG((zend_long) result);",
+                NativeValue::Double => "RETURN_DOUBLE((double) result);",
+                NativeValue::Pointer => "RETURN_LONG((zend_long) (uintptr_t) result);",
+                NativeValue::Buffer => "RETURN_STR(uniffi_lift_buffer(result));",
+            };
+            writeln!(body, "    {ret}").unwrap();
+        }
+
+        Ok(NativeMethod {
+            name: func.name().to_string(),
+            arg_names,
+            body,
+        })
+    }
+}
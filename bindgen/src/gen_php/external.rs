@@ -0,0 +1,153 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Types of another component, which the bindings can use when both components are generated
+//! from the same library: they share its `\FFI`, so the values of one are valid for the other.
+//! The component defining a type names it, the others use its classes by their fully qualified
+//! names.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use uniffi_bindgen::{
+    backend::{CodeType, Literal},
+    interface::Type,
+    ComponentInterface,
+};
+
+use super::{
+    primitives, psr4, rename, Config, OracleContext, OutputLayout, PHPCodeOracle, PhpCodeType,
+};
+
+/// What the bindings of the component defining a type call it, fully qualified.
+#[derive(Debug, Clone)]
+pub struct ExternalTypeNames {
+    type_label: String,
+    doc_type_label: String,
+    ffi_converter_name: String,
+    /// The PHP of each variant, for defaults when the type is an enum.
+    variants: HashMap<String, String>,
+    /// Loads the defining component's library, for types it has to register with Rust first.
+    initialization_fn: Option<String>,
+}
+
+/// The key of a type in `Config::external_types`: its crate, and its name.
+pub fn key(module_path: &str, name: &str) -> (String, String) {
+    let crate_name = module_path.split("::").next().unwrap_or(module_path);
+    (crate_name.to_string(), name.to_string())
+}
+
+/// The names of the types `ci` defines, for the other components generated from the same
+/// library. Types its bindings leave out aren't there.
+pub fn type_names(
+    config: &Config,
+    ci: &ComponentInterface,
+) -> Result<HashMap<(String, String), ExternalTypeNames>> {
+    let namespace = config.namespace()?;
+    let ffi_namespace = match config.layout {
+        OutputLayout::SingleFile => namespace.clone(),
+        OutputLayout::Psr4 => psr4::ffi_namespace(&namespace),
+    };
+    let _context = OracleContext::enter(config, ci);
+    let mut names = HashMap::new();
+    for type_ in ci.iter_types() {
+        let is_definition = matches!(
+            type_,
+            Type::Record { .. } | Type::Enum { .. } | Type::Object { .. } | Type::Custom { .. }
+        );
+        if !is_definition || OracleContext::is_omitted(type_) {
+            continue;
+        }
+        let Some(name) = rename::definition_name(type_) else {
+            continue;
+        };
+        let code_type = PHPCodeOracle.find(type_);
+        // Custom types are exposed as their builtin type, which needs no namespace.
+        let class = match type_ {
+            Type::Custom { .. } => None,
+            _ => Some(PHPCodeOracle.class_name(name)),
+        };
+        let qualify = |php: String| match &class {
+            Some(class) if php == *class || php.starts_with(&format!("{class}::")) => {
+                format!("\\{namespace}\\{php}")
+            }
+            _ => php,
+        };
+        let variants = ci
+            .get_enum_definition(name)
+            .map(|e| {
+                e.variants()
+                    .iter()
+                    .map(|variant| {
+                        let literal = Literal::Enum(variant.name().to_string(), type_.clone());
+                        (
+                            variant.name().to_string(),
+                            qualify(code_type.literal(&literal)),
+                        )
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.insert(
+            key(ci.crate_name(), name),
+            ExternalTypeNames {
+                type_label: qualify(code_type.type_label()),
+                doc_type_label: qualify(code_type.doc_type_label()),
+                ffi_converter_name: format!(
+                    "\\{ffi_namespace}\\{}",
+                    code_type.ffi_converter_name()
+                ),
+                variants,
+                initialization_fn: code_type
+                    .initialization_fn()
+                    .map(|_| format!("\\{ffi_namespace}\\UniffiLib::ffi")),
+            },
+        );
+    }
+    Ok(names)
+}
+
+#[derive(Debug)]
+pub struct ExternalCodeType {
+    id: String,
+    names: ExternalTypeNames,
+}
+
+impl ExternalCodeType {
+    pub fn new(id: String, names: ExternalTypeNames) -> Self {
+        Self { id, names }
+    }
+}
+
+impl CodeType for ExternalCodeType {
+    fn type_label(&self) -> String {
+        self.names.type_label.clone()
+    }
+
+    fn canonical_name(&self) -> String {
+        format!("Type{}", self.id)
+    }
+
+    fn ffi_converter_name(&self) -> String {
+        self.names.ffi_converter_name.clone()
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        match literal {
+            Literal::Enum(variant, _) => self.names.variants[variant].clone(),
+            // Custom types, whose literals are those of their builtin type.
+            _ => primitives::render_literal(literal),
+        }
+    }
+
+    fn initialization_fn(&self) -> Option<String> {
+        self.names.initialization_fn.clone()
+    }
+}
+
+impl PhpCodeType for ExternalCodeType {
+    fn doc_type_label(&self) -> String {
+        self.names.doc_type_label.clone()
+    }
+}
@@ -0,0 +1,74 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A listing of a component's FFI functions, for finding your way around the FFI when debugging
+//! the bindings. Each argument and return value gets the C type we declare it with, and the PHP
+//! type `\FFI` converts it to and from.
+
+use std::fmt::Write;
+
+use uniffi_bindgen::{interface::FfiType, ComponentInterface};
+
+use super::PHPCodeOracle;
+
+pub fn render(ci: &ComponentInterface) -> String {
+    let mut out = format!("// {}\n", ci.namespace());
+    for func in ci.iter_ffi_function_definitions() {
+        let mut rows: Vec<(&str, String, &str)> = func
+            .arguments()
+            .into_iter()
+            .map(|arg| {
+                let type_ = arg.type_();
+                (
+                    arg.name(),
+                    PHPCodeOracle.ffi_type_label(&type_),
+                    php_type_label(&type_),
+                )
+            })
+            .collect();
+        if func.has_rust_call_status_arg() {
+            rows.push(("uniffi_out_err", "RustCallStatus*".into(), "\\FFI\\CData"));
+        }
+        rows.push(match func.return_type() {
+            Some(type_) => (
+                "returns",
+                PHPCodeOracle.ffi_type_label(type_),
+                php_type_label(type_),
+            ),
+            None => ("returns", "void".into(), "null"),
+        });
+
+        let name_width = rows.iter().map(|(name, ..)| name.len()).max().unwrap_or(0);
+        let c_width = rows.iter().map(|(_, c, _)| c.len()).max().unwrap_or(0);
+        writeln!(out, "\n{}", func.name()).unwrap();
+        for (name, c_type, php_type) in rows {
+            writeln!(out, "    {name:name_width$}  {c_type:c_width$}  {php_type}").unwrap();
+        }
+    }
+    out
+}
+
+/// What PHP sees of a value of `ffi_type`: numbers are converted, anything else stays C data.
+fn php_type_label(ffi_type: &FfiType) -> &'static str {
+    match ffi_type {
+        FfiType::Int8
+        | FfiType::UInt8
+        | FfiType::Int16
+        | FfiType::UInt16
+        | FfiType::Int32
+        | FfiType::UInt32
+        | FfiType::Int64
+        | FfiType::UInt64
+        | FfiType::Handle => "int",
+        FfiType::Float32 | FfiType::Float64 => "float",
+        FfiType::RustArcPtr(_)
+        | FfiType::RustBuffer(_)
+        | FfiType::RustCallStatus
+        | FfiType::ForeignBytes
+        | FfiType::Callback(_)
+        | FfiType::Struct(_)
+        | FfiType::Reference(_)
+        | FfiType::VoidPointer => "\\FFI\\CData",
+    }
+}
@@ -0,0 +1,386 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Format the generated code, with php-cs-fixer or phpcbf when they're installed, or failing
+//! that with a simple pretty-printer of our own.
+
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+
+/// The `formatter` option. By default we use the first external formatter found on `PATH`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Formatter {
+    PhpCsFixer,
+    Phpcbf,
+    /// Only ever use our own pretty-printer.
+    Builtin,
+}
+
+impl Formatter {
+    fn command(&self) -> Option<&'static str> {
+        match self {
+            Formatter::PhpCsFixer => Some("php-cs-fixer"),
+            Formatter::Phpcbf => Some("phpcbf"),
+            Formatter::Builtin => None,
+        }
+    }
+
+    fn args(&self) -> &'static [&'static str] {
+        match self {
+            Formatter::PhpCsFixer => &["fix", "--quiet", "--using-cache=no"],
+            Formatter::Phpcbf => &["-q", "--standard=PSR12"],
+            Formatter::Builtin => &[],
+        }
+    }
+
+    fn succeeded(&self, code: Option<i32>) -> bool {
+        match self {
+            // phpcbf exits with 1 when it fixed something.
+            Formatter::Phpcbf => matches!(code, Some(0 | 1)),
+            _ => code == Some(0),
+        }
+    }
+}
+
+/// The external formatter to run, if any is configured (or unconfigured) and installed.
+pub fn find_external(configured: Option<Formatter>) -> Option<(Formatter, Utf8PathBuf)> {
+    let candidates = match configured {
+        Some(formatter) => vec![formatter],
+        None => vec![Formatter::PhpCsFixer, Formatter::Phpcbf],
+    };
+    candidates.into_iter().find_map(|formatter| {
+        let path = find_on_path(formatter.command()?)?;
+        Some((formatter, path))
+    })
+}
+
+fn find_on_path(command: &str) -> Option<Utf8PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(command))
+        .find(|path| path.is_file())
+        .and_then(|path| Utf8PathBuf::from_path_buf(path).ok())
+}
+
+/// Run an external formatter over the written files. Failing to format isn't fatal, the code
+/// works all the same.
+pub fn run_external(formatter: Formatter, command: &Utf8PathBuf, files: &[Utf8PathBuf]) {
+    let status = Command::new(command)
+        .args(formatter.args())
+        .args(files)
+        .status();
+    match status {
+        Ok(status) if formatter.succeeded(status.code()) => {}
+        Ok(status) => eprintln!("warning: unable to auto-format PHP using {command}: {status}"),
+        Err(e) => eprintln!("warning: unable to auto-format PHP using {command}: {e:?}"),
+    }
+}
+
+/// Run an external formatter over files we only have in memory, by way of a scratch directory.
+/// Only the PHP files are formatted, the others come back as they were.
+pub fn run_external_in_memory(
+    formatter: Formatter,
+    command: &Utf8PathBuf,
+    files: Vec<(Utf8PathBuf, String)>,
+) -> Result<Vec<(Utf8PathBuf, String)>> {
+    let scratch_dir =
+        std::env::temp_dir().join(format!("uniffi-bindgen-php-{}", std::process::id()));
+    let scratch_dir = Utf8PathBuf::from_path_buf(scratch_dir)
+        .map_err(|dir| anyhow!("the temporary directory `{}` isn't UTF-8", dir.display()))?;
+    let mut scratch_files = Vec::new();
+    for (path, contents) in &files {
+        if is_php(path) {
+            let scratch_file = scratch_dir.join(path);
+            if let Some(dir) = scratch_file.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&scratch_file, contents)?;
+            scratch_files.push(scratch_file);
+        }
+    }
+    run_external(formatter, command, &scratch_files);
+
+    let formatted = files
+        .into_iter()
+        .map(|(path, contents)| {
+            if is_php(&path) {
+                let formatted = fs::read_to_string(scratch_dir.join(&path))?;
+                Ok((path, formatted))
+            } else {
+                Ok((path, contents))
+            }
+        })
+        .collect();
+    if scratch_dir.exists() {
+        fs::remove_dir_all(&scratch_dir)?;
+    }
+    formatted
+}
+
+/// Whether `path` is a PHP file, as opposed to the likes of `composer.json`.
+pub fn is_php(path: &Utf8Path) -> bool {
+    path.extension() == Some("php")
+}
+
+/// Re-indent `code` by its nesting, four spaces per level, and tidy up blank lines: no more than
+/// one in a row, and none just inside braces.
+///
+/// This understands enough PHP to skip over strings, comments and nowdocs, and to indent the
+/// bodies of `switch` cases and continuation lines (`->foo()`, `? a`, `: b`).
+pub fn pretty_print(code: &str) -> String {
+    let mut printer = Printer::default();
+    let mut out = String::with_capacity(code.len());
+    let mut pending_blank = false;
+    let mut after_open = true;
+
+    for line in code.lines() {
+        if let Some(terminator) = &printer.heredoc {
+            if line.trim_start().starts_with(terminator.as_str()) {
+                printer.heredoc = None;
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+            continue;
+        }
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            pending_blank = !after_open;
+            continue;
+        }
+        if pending_blank && !trimmed.starts_with('}') {
+            out.push('\n');
+        }
+        pending_blank = false;
+
+        let indent = printer.indent_of(trimmed);
+        if printer.in_block_comment && trimmed.starts_with('*') {
+            // Docblock lines line up under the `/**`.
+            out.push_str(&"    ".repeat(indent));
+            out.push(' ');
+        } else {
+            out.push_str(&"    ".repeat(indent));
+        }
+        out.push_str(trimmed);
+        out.push('\n');
+
+        printer.scan(trimmed);
+        after_open = trimmed.ends_with('{');
+    }
+    out
+}
+
+#[derive(Default)]
+struct Printer {
+    /// The open brackets, with whether each is the body of a `switch` which has seen a `case`.
+    stack: Vec<Bracket>,
+    in_block_comment: bool,
+    /// The terminator of the heredoc or nowdoc we're in.
+    heredoc: Option<String>,
+}
+
+struct Bracket {
+    /// Only the first of the brackets left open on a line indents the following lines, and
+    /// bracketed namespaces don't indent their contents at all.
+    indents: bool,
+    is_switch: bool,
+    in_case: bool,
+}
+
+impl Printer {
+    fn indent_of(&mut self, line: &str) -> usize {
+        if self.in_block_comment {
+            return self.stack.iter().filter(|b| b.indents).count();
+        }
+        let closers = line
+            .chars()
+            .take_while(|c| matches!(c, '}' | ')' | ']'))
+            .count()
+            .min(self.stack.len());
+        let still_open = self.stack.len() - closers;
+        let open = &mut self.stack[..still_open];
+        let is_case_label = line.starts_with("case ") || line.starts_with("default:");
+        if is_case_label && closers == 0 {
+            if let Some(top) = open.last_mut().filter(|b| b.is_switch) {
+                top.in_case = true;
+            }
+        }
+        let mut indent =
+            open.iter().filter(|b| b.indents).count() + open.iter().filter(|b| b.in_case).count();
+        if is_case_label && closers == 0 && open.last().is_some_and(|b| b.is_switch) {
+            indent -= 1;
+        }
+        let is_continuation = ["->", "?->", "? ", ": ", ". ", "&& ", "|| "]
+            .iter()
+            .any(|prefix| line.starts_with(prefix));
+        if is_continuation {
+            indent += 1;
+        }
+        indent
+    }
+
+    /// Track the brackets opened and closed on `line`.
+    fn scan(&mut self, line: &str) {
+        let is_switch = line.starts_with("switch ") || line.starts_with("switch(");
+        let is_namespace = line.starts_with("namespace ");
+        let open_before = self.stack.len();
+        let mut chars = line.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if self.in_block_comment {
+                if c == '*' && chars.peek().map(|(_, c)| *c) == Some('/') {
+                    chars.next();
+                    self.in_block_comment = false;
+                }
+                continue;
+            }
+            match c {
+                '\'' | '"' => {
+                    while let Some((_, s)) = chars.next() {
+                        if s == '\\' {
+                            chars.next();
+                        } else if s == c {
+                            break;
+                        }
+                    }
+                }
+                '/' if chars.peek().map(|(_, c)| *c) == Some('/') => return,
+                '#' if chars.peek().map(|(_, c)| *c) != Some('[') => return,
+                '/' if chars.peek().map(|(_, c)| *c) == Some('*') => {
+                    chars.next();
+                    self.in_block_comment = true;
+                }
+                '<' if line[i..].starts_with("<<<") => {
+                    let terminator = line[i + 3..].trim().trim_matches(|c| c == '\'' || c == '"');
+                    self.heredoc = Some(terminator.to_string());
+                    return;
+                }
+                '{' | '(' | '[' => self.stack.push(Bracket {
+                    indents: self.stack.len() <= open_before && !(c == '{' && is_namespace),
+                    is_switch: c == '{' && is_switch,
+                    in_case: false,
+                }),
+                '}' | ')' | ']' => {
+                    self.stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `code` without its first line break, the indentation of its closing quote and common
+    /// indentation, which keeps the tests readable.
+    fn dedent(code: &str) -> String {
+        let code = code
+            .strip_prefix('\n')
+            .unwrap_or(code)
+            .trim_end_matches(' ');
+        let indent = code
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        code.lines()
+            .map(|line| format!("{}\n", line.get(indent..).unwrap_or("").trim_end()))
+            .collect()
+    }
+
+    #[test]
+    fn indents_by_nesting() {
+        let code = "namespace Acme {\nfinal class A\n{\n\n\npublic function f(): string\n{\n\nreturn '{';\n}\n\n}\n}\n";
+        assert_eq!(
+            pretty_print(code),
+            dedent(
+                "
+                namespace Acme {
+                final class A
+                {
+                    public function f(): string
+                    {
+                        return '{';
+                    }
+                }
+                }
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_heredocs_alone() {
+        let code = "const CDEF = <<<'CDEF'\n  typedef struct {\n      int x;\n  } S;\nCDEF;\nfunction f()\n{\nreturn 1;\n}\n";
+        assert_eq!(
+            pretty_print(code),
+            dedent(
+                "
+                const CDEF = <<<'CDEF'
+                  typedef struct {
+                      int x;
+                  } S;
+                CDEF;
+                function f()
+                {
+                    return 1;
+                }
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn indents_switch_cases() {
+        let code = "switch ($code) {\ncase 0:\nreturn;\ncase 1:\nif ($x) {\nthrow $e;\n}\nbreak;\ndefault:\nthrow $f;\n}\n";
+        assert_eq!(
+            pretty_print(code),
+            dedent(
+                "
+                switch ($code) {
+                    case 0:
+                        return;
+                    case 1:
+                        if ($x) {
+                            throw $e;
+                        }
+                        break;
+                    default:
+                        throw $f;
+                }
+                "
+            )
+        );
+    }
+
+    #[test]
+    fn indents_continuation_lines() {
+        let code = "$x = $a\n->b()\n?->c();\n$y = $ok\n? 1\n: 2;\n/**\n* Doc.\n*/\nf(\n$a,\n);\n";
+        assert_eq!(
+            pretty_print(code),
+            dedent(
+                "
+                $x = $a
+                    ->b()
+                    ?->c();
+                $y = $ok
+                    ? 1
+                    : 2;
+                /**
+                 * Doc.
+                 */
+                f(
+                    $a,
+                );
+                "
+            )
+        );
+    }
+}
@@ -2,127 +2,270 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use askama::Template;
+use camino::Utf8PathBuf;
 use fs_err::{self as fs};
-use heck::{ToLowerCamelCase, ToShoutySnakeCase, ToUpperCamelCase};
-use once_cell::sync::Lazy;
+use heck::ToUpperCamelCase;
 use serde::{Deserialize, Serialize};
 use std::{
     cell::RefCell,
     collections::{HashMap, HashSet},
+    io::Write,
 };
 use uniffi_bindgen::{
     backend::{CodeType, TemplateExpression},
-    interface::{FfiType, Object},
+    interface::*,
     Component, ComponentInterface, GenerationSettings,
 };
-use uniffi_meta::Type;
-
-static KEYWORDS: Lazy<HashSet<String>> = Lazy::new(|| {
-    [
-        // Keywords used in declarations:
-        "associatedtype",
-        "class",
-        "deinit",
-        "enum",
-        "extension",
-        "fileprivate",
-        "func",
-        "import",
-        "init",
-        "inout",
-        "internal",
-        "let",
-        "open",
-        "operator",
-        "private",
-        "precedencegroup",
-        "protocol",
-        "public",
-        "rethrows",
-        "static",
-        "struct",
-        "subscript",
-        "typealias",
-        "var",
-        // Keywords used in statements:
-        "break",
-        "case",
-        "catch",
-        "continue",
-        "default",
-        "defer",
-        "do",
-        "else",
-        "fallthrough",
-        "for",
-        "guard",
-        "if",
-        "in",
-        "repeat",
-        "return",
-        "throw",
-        "switch",
-        "where",
-        "while",
-        // Keywords used in expressions and types:
-        "Any",
-        "as",
-        "await",
-        "catch",
-        "false",
-        "is",
-        "nil",
-        "rethrows",
-        "self",
-        "Self",
-        "super",
-        "throw",
-        "throws",
-        "true",
-        "try",
-    ]
-    .iter()
-    .map(ToString::to_string)
-    .collect::<HashSet<_>>()
-});
-
-pub fn quote_general_keyword(nm: String) -> String {
-    if KEYWORDS.contains(&nm) {
-        format!("`{nm}`")
-    } else {
-        nm
-    }
-}
 
-static ARG_KEYWORDS: Lazy<HashSet<String>> = Lazy::new(|| {
-    ["inout", "var", "let"]
-        .iter()
-        .map(ToString::to_string)
-        .collect::<HashSet<_>>()
-});
-
-pub fn quote_arg_keyword(nm: String) -> String {
-    if ARG_KEYWORDS.contains(&nm) {
-        format!("`{nm}`")
-    } else {
-        nm
-    }
-}
+mod callback_interface;
+mod composer;
+mod compounds;
+mod custom;
+mod enum_;
+mod extension;
+mod external;
+mod ffi_listing;
+mod formatter;
+mod naming;
+mod object;
+mod primitives;
+mod psr4;
+mod record;
+mod rename;
+mod unsupported;
+
+pub use naming::{php_escape, NameContext};
+
+/// The comment at the top of every generated file, without the comment syntax.
+pub const GENERATED_HEADER: &str = "This file was generated by uniffi-bindgen-php, do not edit.";
 
 pub struct Bindings {
     library: String,
+    /// What the library leaves out, when the config allows that.
+    unsupported: unsupported::Report,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Config {
     pub(super) module_name: Option<String>,
     pub(super) cdylib_name: Option<String>,
+    /// The `FFI_SCOPE` of the C header, which `FFI::scope()` finds the preloaded FFI by.
+    ffi_scope: Option<String>,
+    /// The `FFI_LIB` of the C header, which `FFI::load()` opens.
+    ffi_lib: Option<String>,
+    namespace: Option<String>,
+    facade_class: Option<String>,
     #[serde(default)]
     custom_types: HashMap<String, CustomTypeConfig>,
     #[serde(default)]
     external_packages: HashMap<String, String>,
+    #[serde(default)]
+    async_runtime: AsyncRuntime,
+    /// Promise that the library only wakes the futures of its async functions from the thread
+    /// polling them, which the bindings need: Rust tells PHP a future can make progress by
+    /// calling into it, and PHP can't be entered from another thread. Without this async
+    /// functions are unsupported.
+    ///
+    /// Futures running on a multi-threaded executor, like tokio's, are woken from its worker
+    /// threads, so libraries using one can't set this and have no async bindings.
+    #[serde(default)]
+    same_thread_wakers: bool,
+    #[serde(default)]
+    backend: Backend,
+    #[serde(default)]
+    layout: OutputLayout,
+    #[serde(default)]
+    php_version: PhpVersion,
+    composer: Option<composer::ComposerConfig>,
+    formatter: Option<formatter::Formatter>,
+    #[serde(default)]
+    naming: naming::Naming,
+    /// PHP names for Rust items, see the `rename` module for how items are named.
+    #[serde(default)]
+    rename: HashMap<String, String>,
+    /// Rust items to leave out of the bindings.
+    #[serde(default)]
+    exclude: Vec<String>,
+    /// Leave out what the bindings can't handle instead of failing, see the `unsupported` module.
+    #[serde(default)]
+    allow_unsupported: bool,
+    /// Write the C header and a `preload.php` declaring the FFI from opcache preloading, and
+    /// look for it with `FFI::scope()` before declaring it again.
+    #[serde(default)]
+    preload: bool,
+    /// The FFI declarations of every component generated from the same library, filled in by
+    /// `update_component_configs` when there's more than one, so they can share an `\FFI`.
+    #[serde(skip)]
+    shared_ffi_definitions: Option<String>,
+    /// How the other components generated from the same library name their types, by crate and
+    /// type name, filled in by `update_component_configs` for `Type::External`.
+    #[serde(skip)]
+    external_types: HashMap<(String, String), external::ExternalTypeNames>,
+}
+
+/// How async functions are exposed to PHP.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AsyncRuntime {
+    /// Async functions block until done, suspending the current `\Fiber` (if any) while they wait.
+    #[default]
+    Fiber,
+    /// Async functions return an `Amp\Future`, resumed from the Revolt event loop.
+    Amp,
+    /// Async functions return a ReactPHP `PromiseInterface`, resolved from the ReactPHP event loop.
+    React,
+}
+
+impl AsyncRuntime {
+    /// The type async functions return instead of their result, if any.
+    pub fn awaitable_type(&self) -> Option<&'static str> {
+        match self {
+            AsyncRuntime::Fiber => None,
+            AsyncRuntime::Amp => Some("\\Amp\\Future"),
+            AsyncRuntime::React => Some("\\React\\Promise\\PromiseInterface"),
+        }
+    }
+}
+
+/// How the bindings call into Rust.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Through `ext-ffi`, loading the cdylib at runtime.
+    #[default]
+    Ffi,
+    /// Through a PHP extension we generate the C source of, see the `extension` module. Calls
+    /// only go from PHP into Rust, so components with callback interfaces, trait interfaces PHP
+    /// can implement or async functions are refused.
+    Extension,
+}
+
+impl Backend {
+    pub fn is_extension(&self) -> bool {
+        *self == Backend::Extension
+    }
+
+    /// What PHP gets for a pointer to a Rust object.
+    pub fn pointer_type(&self) -> &'static str {
+        match self {
+            Backend::Ffi => "\\FFI\\CData",
+            Backend::Extension => "int",
+        }
+    }
+
+    /// What PHP gets for a `RustBuffer`.
+    pub fn buffer_type(&self) -> &'static str {
+        match self {
+            Backend::Ffi => "\\FFI\\CData",
+            Backend::Extension => "string",
+        }
+    }
+
+    /// What PHP passes for the `RustCallStatus*` of a call.
+    pub fn status_type(&self) -> &'static str {
+        match self {
+            Backend::Ffi => "\\FFI\\CData",
+            Backend::Extension => "UniffiRustCallStatus",
+        }
+    }
+}
+
+/// The oldest PHP version the generated code has to run on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PhpVersion {
+    #[serde(rename = "7.4")]
+    Php74,
+    #[default]
+    #[serde(rename = "8.1")]
+    Php81,
+    #[serde(rename = "8.3")]
+    Php83,
+}
+
+impl PhpVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PhpVersion::Php74 => "7.4",
+            PhpVersion::Php81 => "8.1",
+            PhpVersion::Php83 => "8.3",
+        }
+    }
+
+    pub fn features(&self) -> PhpFeatures {
+        let php8 = *self >= PhpVersion::Php81;
+        PhpFeatures {
+            php8_syntax: php8,
+            mixed_type: php8,
+            union_types: php8,
+            static_return_type: php8,
+            native_enums: php8,
+            readonly_properties: php8,
+            readonly_classes: *self >= PhpVersion::Php83,
+            first_class_callables: php8,
+            fibers: php8,
+            never_type: php8,
+        }
+    }
+}
+
+impl std::str::FromStr for PhpVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "7.4" => Ok(PhpVersion::Php74),
+            "8.1" => Ok(PhpVersion::Php81),
+            "8.3" => Ok(PhpVersion::Php83),
+            _ => bail!("Unsupported PHP version `{s}`, expected one of 7.4, 8.1 or 8.3"),
+        }
+    }
+}
+
+/// The language features the templates may use, derived from the configured `PhpVersion`.
+///
+/// Templates should check these rather than the version itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhpFeatures {
+    /// Constructor property promotion, `match`, `?->`, `throw` expressions and trailing commas in
+    /// parameter lists (PHP 8.0).
+    pub php8_syntax: bool,
+    /// The `mixed` type (PHP 8.0).
+    pub mixed_type: bool,
+    /// Union types such as `int|float` (PHP 8.0).
+    pub union_types: bool,
+    /// `static` as a return type (PHP 8.0).
+    pub static_return_type: bool,
+    /// Flat enums become native enums rather than classes of `int` constants (PHP 8.1).
+    pub native_enums: bool,
+    /// Immutable values get `readonly` properties rather than private ones with getters (PHP 8.1).
+    pub readonly_properties: bool,
+    /// Immutable values are `readonly` classes (PHP 8.2).
+    pub readonly_classes: bool,
+    /// `Foo::bar(...)` rather than `\Closure::fromCallable()` (PHP 8.1).
+    pub first_class_callables: bool,
+    /// `\Fiber`, which async calls suspend while they wait (PHP 8.1).
+    pub fibers: bool,
+    /// `never` as the return type of stubs which always throw (PHP 8.1).
+    pub never_type: bool,
+}
+
+impl Default for PhpFeatures {
+    fn default() -> Self {
+        PhpVersion::default().features()
+    }
+}
+
+/// How the generated code is split into files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputLayout {
+    /// Everything in a single `{module_name}.php`, to be `require`d.
+    #[default]
+    SingleFile,
+    /// One file per class under `src/`, following PSR-4, for Composer's autoloader.
+    Psr4,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -134,24 +277,333 @@ pub struct CustomTypeConfig {
 }
 
 impl Config {
-    pub fn module_name(&self) -> String {
+    /// The name of the generated files. Filled in from the component by
+    /// `update_component_configs` if the config doesn't set it.
+    pub fn module_name(&self) -> Result<String> {
         self.module_name
+            .clone()
+            .context("the config has no `module_name`, and none was filled in from the component")
+    }
+
+    /// The PHP namespace everything is generated in.
+    pub fn namespace(&self) -> Result<String> {
+        let namespace = self
+            .namespace
             .as_ref()
-            .expect("module name should have been set in update_component_configs")
+            .context("the config has no `namespace`, and none was filled in from the component")?;
+        Ok(namespace.trim_matches('\\').to_string())
+    }
+
+    pub fn php_version(&self) -> PhpVersion {
+        self.php_version
+    }
+
+    pub fn features(&self) -> PhpFeatures {
+        self.php_version.features()
+    }
+
+    /// When set, top-level functions become static methods of this class rather than
+    /// functions in the namespace.
+    pub fn facade_class(&self) -> Option<&str> {
+        self.facade_class.as_deref()
+    }
+
+    pub fn cdylib_name(&self) -> String {
+        self.cdylib_name.clone().unwrap_or_else(|| "uniffi".into())
+    }
+
+    /// The name the FFI is preloaded under, the module name unless the config says otherwise.
+    pub fn ffi_scope(&self) -> Result<String> {
+        match &self.ffi_scope {
+            Some(scope) => Ok(scope.clone()),
+            None => self.module_name(),
+        }
+    }
+
+    /// The C declarations `\FFI` gets for `ci`, which include those of the other components
+    /// generated from the same library.
+    fn ffi_definitions(&self, ci: &ComponentInterface) -> Result<String> {
+        match &self.shared_ffi_definitions {
+            Some(definitions) => Ok(definitions.clone()),
+            None => render_ffi_definitions(&[ci]),
+        }
+    }
+
+    /// The name of the PHP extension the `extension` backend generates.
+    pub fn extension_name(&self) -> Result<String> {
+        Ok(format!("uniffi_{}", self.module_name()?))
+    }
+
+    /// The library `FFI::load()` opens. Unless the config says otherwise, that's the file name of
+    /// the cdylib on the platform we're running on, for the dynamic linker to look up.
+    pub fn ffi_lib(&self) -> String {
+        self.ffi_lib
             .clone()
+            .unwrap_or_else(|| cdylib_file_name(&self.cdylib_name()))
+    }
+}
+
+/// The file name of the cdylib called `name` on the host OS.
+pub fn cdylib_file_name(name: &str) -> String {
+    if cfg!(target_os = "macos") {
+        format!("lib{name}.dylib")
+    } else if cfg!(target_os = "windows") {
+        format!("{name}.dll")
+    } else {
+        format!("lib{name}.so")
+    }
+}
+
+thread_local! {
+    static ORACLE_CONTEXT: RefCell<OracleContext> = RefCell::new(OracleContext::default());
+}
+
+/// What the code types need to know about the component being rendered, which they can't get
+/// from the `Type` they're created from.
+#[derive(Default)]
+struct OracleContext {
+    /// Flat enums which are classes of `int` constants, so their values are plain `int`s.
+    int_enums: HashSet<String>,
+    naming: naming::Naming,
+    /// The case of enum variants: the `enum_cases` naming, or `constants` when enums are
+    /// classes of constants.
+    variant_case: naming::ConstantCase,
+    renames: HashMap<String, String>,
+    excluded: HashSet<String>,
+    unsupported_types: HashSet<String>,
+    /// The functions, methods and constructors which are stubs, with the reason why.
+    unsupported_callables: HashMap<String, String>,
+    external_types: HashMap<(String, String), external::ExternalTypeNames>,
+}
+
+impl OracleContext {
+    /// Set the context for the code types created until the returned guard is dropped.
+    fn enter(config: &Config, ci: &ComponentInterface) -> OracleContextGuard {
+        let int_enums = if config.features().native_enums {
+            HashSet::new()
+        } else {
+            ci.enum_definitions()
+                .filter(|e| e.is_flat() && !ci.is_name_used_as_error(e.name()))
+                .map(|e| e.name().to_string())
+                .collect()
+        };
+        let naming = config.naming;
+        let variant_case = if config.features().native_enums {
+            naming.enum_cases
+        } else {
+            naming.constants
+        };
+        let mut unsupported_types = HashSet::new();
+        let mut unsupported_callables = HashMap::new();
+        for item in unsupported::Report::find(config, ci).unsupported {
+            match item.kind {
+                unsupported::ItemKind::Type => {
+                    unsupported_types.insert(item.item);
+                }
+                _ => {
+                    unsupported_callables.insert(item.item, item.reason);
+                }
+            }
+        }
+        ORACLE_CONTEXT.with(|c| {
+            *c.borrow_mut() = OracleContext {
+                int_enums,
+                naming,
+                variant_case,
+                renames: config.rename.clone(),
+                excluded: config.exclude.iter().cloned().collect(),
+                unsupported_types,
+                unsupported_callables,
+                external_types: config.external_types.clone(),
+            }
+        });
+        OracleContextGuard
+    }
+
+    fn is_int_enum(name: &str) -> bool {
+        ORACLE_CONTEXT.with(|c| c.borrow().int_enums.contains(name))
+    }
+
+    fn naming() -> naming::Naming {
+        ORACLE_CONTEXT.with(|c| c.borrow().naming)
+    }
+
+    fn variant_case() -> naming::ConstantCase {
+        ORACLE_CONTEXT.with(|c| c.borrow().variant_case)
+    }
+
+    /// The PHP name the config gives the item called `key`, if any.
+    fn renamed(key: &str) -> Option<String> {
+        ORACLE_CONTEXT.with(|c| c.borrow().renames.get(key).cloned())
+    }
+
+    fn is_excluded(key: &str) -> bool {
+        ORACLE_CONTEXT.with(|c| c.borrow().excluded.contains(key))
+    }
+
+    /// The excluded type which `type_` is, or contains.
+    fn excluded_type(type_: &Type) -> Option<String> {
+        ORACLE_CONTEXT.with(|c| {
+            let context = c.borrow();
+            let excluded = context.excluded.iter().map(String::as_str).collect();
+            rename::excluded_type(type_, &excluded)
+        })
+    }
+
+    /// Whether `type_` is left out of the bindings, being (or containing) an excluded or
+    /// unsupported type.
+    fn is_omitted(type_: &Type) -> bool {
+        Self::excluded_type(type_).is_some()
+            || ORACLE_CONTEXT.with(|c| {
+                let context = c.borrow();
+                let is_unsupported = |name: &str| context.unsupported_types.contains(name);
+                unsupported::unsupported_type(type_, &is_unsupported).is_some()
+            })
+    }
+
+    /// Why the function, method or constructor called `key` is a stub, if it is.
+    fn unsupported_reason(key: &str) -> Option<String> {
+        ORACLE_CONTEXT.with(|c| c.borrow().unsupported_callables.get(key).cloned())
+    }
+
+    fn has_unsupported_callables() -> bool {
+        ORACLE_CONTEXT.with(|c| !c.borrow().unsupported_callables.is_empty())
+    }
+
+    /// What the component defining the external type calls it, if it's generated from the same
+    /// library.
+    fn external_type(module_path: &str, name: &str) -> Option<external::ExternalTypeNames> {
+        let key = external::key(module_path, name);
+        ORACLE_CONTEXT.with(|c| c.borrow().external_types.get(&key).cloned())
+    }
+}
+
+struct OracleContextGuard;
+
+impl Drop for OracleContextGuard {
+    fn drop(&mut self) {
+        ORACLE_CONTEXT.with(|c| *c.borrow_mut() = OracleContext::default());
     }
 }
 
 pub fn generate_bindings(config: &Config, ci: &ComponentInterface) -> Result<Bindings> {
-    let library = PhpWrapper::new(config.clone(), ci)
+    if config.async_runtime == AsyncRuntime::Amp && !config.features().fibers {
+        bail!(
+            "`async_runtime = \"amp\"` needs PHP 8.1 or later, but `php_version` is {}",
+            config.php_version().as_str()
+        );
+    }
+    if config.preload && config.backend.is_extension() {
+        bail!(
+            "`preload` declares the FFI for `ext-ffi`, which the `extension` backend doesn't use"
+        );
+    }
+    if config.backend.is_extension() {
+        extension::check_supported(ci)?;
+    }
+    rename::validate(config, ci)?;
+    unsupported::check_defaults(ci)?;
+    let unsupported = unsupported::Report::find(config, ci);
+    unsupported.check(config)?;
+    let _context = OracleContext::enter(config, ci);
+    let library = PhpWrapper::new(config.clone(), ci)?
         .render()
         .context("failed to render PHP library")?;
 
-    Ok(Bindings { library })
+    Ok(Bindings {
+        library,
+        unsupported,
+    })
+}
+
+/// Render IDE stubs for the component: the signatures and documentation of everything the
+/// bindings declare, without the FFI plumbing.
+pub fn generate_stubs(config: &Config, ci: &ComponentInterface) -> Result<String> {
+    if config.backend.is_extension() {
+        extension::check_supported(ci)?;
+    }
+    rename::validate(config, ci)?;
+    unsupported::check_defaults(ci)?;
+    unsupported::Report::find(config, ci).check(config)?;
+    let _context = OracleContext::enter(config, ci);
+    StubsWrapper::new(config.clone(), ci)?
+        .render()
+        .context("failed to render PHP stubs")
+}
+
+/// Render the C declarations of the FFI of `cis`, declaring what they have in common once.
+fn render_ffi_definitions(cis: &[&ComponentInterface]) -> Result<String> {
+    let mut seen = HashSet::new();
+    let definitions = cis
+        .iter()
+        .flat_map(|ci| ci.ffi_definitions())
+        .filter(|def| seen.insert((std::mem::discriminant(def), def.name().to_string())))
+        .collect();
+    FfiDefinitions { definitions }
+        .render()
+        .context("failed to render FFI definitions")
+}
+
+/// Render the C declarations of the component's FFI, as a header for `FFI::load()`.
+pub fn generate_header(config: &Config, ci: &ComponentInterface) -> Result<String> {
+    let _context = OracleContext::enter(config, ci);
+    FfiHeader {
+        ci,
+        ffi_definitions: config.ffi_definitions(ci)?,
+        ffi_scope: config.ffi_scope()?,
+        ffi_lib: config.ffi_lib(),
+    }
+    .render()
+    .context("failed to render C header")
+}
+
+/// The script for `opcache.preload`, shared by the components of an output directory.
+const PRELOAD_FILE: &str = "preload.php";
+
+/// Render the `preload.php` loading the header of each component with `preload` set, if any.
+/// Components sharing an `FFI_SCOPE` also share their declarations, so one header is enough.
+pub fn generate_preload(components: &[Component<Config>]) -> Result<Option<String>> {
+    let mut scopes = HashSet::new();
+    let mut headers = Vec::new();
+    for Component { config, .. } in components.iter().filter(|c| c.config.preload) {
+        if scopes.insert(config.ffi_scope()?) {
+            headers.push(format!("{}.h", config.module_name()?));
+        }
+    }
+    if headers.is_empty() {
+        return Ok(None);
+    }
+    let script = PreloadScript { headers }
+        .render()
+        .context("failed to render preload script")?;
+    Ok(Some(script))
+}
+
+/// What `BindingGeneratorPHP` makes of each component.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Output {
+    /// Write the bindings.
+    #[default]
+    Bindings,
+    /// Render the bindings and fail if they differ from the files in the output directory.
+    Check,
+    /// Write IDE stubs instead of the bindings.
+    Stubs,
+    /// Write a C header declaring the FFI.
+    Header,
+    /// Print the FFI functions, with their C and PHP types.
+    FfiListing,
+    /// Write the bindings with the `extension` backend, along with the extension's C source.
+    Extension,
 }
 
 pub struct BindingGeneratorPHP {
     pub try_format_code: bool,
+    /// Overrides `php_version` from the config.
+    pub php_version: Option<PhpVersion>,
+    pub output: Output,
+    /// Sets `allow_unsupported` in the config.
+    pub allow_unsupported: bool,
 }
 
 impl uniffi_bindgen::BindingGenerator for BindingGeneratorPHP {
@@ -173,6 +625,56 @@ impl uniffi_bindgen::BindingGenerator for BindingGeneratorPHP {
             c.config
                 .module_name
                 .get_or_insert_with(|| c.ci.namespace().into());
+            c.config
+                .namespace
+                .get_or_insert_with(|| c.ci.namespace().to_upper_camel_case());
+            if let Some(php_version) = self.php_version {
+                c.config.php_version = php_version;
+            }
+            if self.allow_unsupported {
+                c.config.allow_unsupported = true;
+            }
+            if self.output == Output::Extension {
+                c.config.backend = Backend::Extension;
+            }
+            if let Some(cdylib) = &settings.cdylib {
+                c.config.cdylib_name.get_or_insert_with(|| cdylib.clone());
+            }
+        }
+
+        // Components from the same library share its `\FFI`, which has to declare all of them,
+        // and when preloaded the header of any of them will do.
+        let mut libraries: HashMap<String, Vec<usize>> = HashMap::new();
+        for (i, c) in components.iter().enumerate() {
+            if let Some(cdylib) = &c.config.cdylib_name {
+                libraries.entry(cdylib.clone()).or_default().push(i);
+            }
+        }
+        for (cdylib, members) in libraries {
+            if members.len() < 2 {
+                continue;
+            }
+            let cis: Vec<_> = members.iter().map(|&i| &components[i].ci).collect();
+            let definitions = render_ffi_definitions(&cis)?;
+            // They can also use each other's types. Which ones a component has depends on the
+            // types it uses from the others, so keep going until we stop finding new ones.
+            let mut external_types = HashMap::new();
+            loop {
+                let found = external_types.len();
+                for &i in &members {
+                    let Component { ci, config, .. } = &mut components[i];
+                    config.external_types = external_types.clone();
+                    external_types.extend(external::type_names(config, ci)?);
+                }
+                if external_types.len() == found {
+                    break;
+                }
+            }
+            for i in members {
+                let config = &mut components[i].config;
+                config.shared_ffi_definitions = Some(definitions.clone());
+                config.ffi_scope.get_or_insert_with(|| cdylib.clone());
+            }
         }
         Ok(())
     }
@@ -182,35 +684,254 @@ impl uniffi_bindgen::BindingGenerator for BindingGeneratorPHP {
         settings: &uniffi_bindgen::GenerationSettings,
         components: &[uniffi_bindgen::Component<Self::Config>],
     ) -> anyhow::Result<()> {
+        if components
+            .iter()
+            .filter(|c| c.config.composer.is_some())
+            .count()
+            > 1
+        {
+            bail!("Only one component can set `[bindings.php.composer]` for an output directory");
+        }
+
+        match self.output {
+            Output::Bindings | Output::Extension => {}
+            Output::Check => return self.check_bindings(settings, components),
+            Output::Stubs => return self.write_stubs(settings, components),
+            Output::Header => return write_headers(settings, components),
+            Output::FfiListing => {
+                let mut stdout = std::io::stdout().lock();
+                for Component { ci, .. } in components {
+                    stdout.write_all(ffi_listing::render(ci).as_bytes())?;
+                }
+                return Ok(());
+            }
+        }
+
         for Component { ci, config, .. } in components {
-            let Bindings { library } = generate_bindings(config, ci)?;
+            let external_formatter = self.external_formatter(config);
+            let files = self.bindings_files(
+                config,
+                ci,
+                self.try_format_code && external_formatter.is_none(),
+            )?;
+            let mut written = Vec::with_capacity(files.len());
+            for (path, contents) in files {
+                let path = settings.out_dir.join(path);
+                if let Some(dir) = path.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::write(&path, contents)?;
+                written.push(path);
+            }
+            if let Some((formatter, command)) = external_formatter {
+                written.retain(|path| formatter::is_php(path));
+                formatter::run_external(formatter, &command, &written);
+            }
+        }
+        if let Some(preload) = generate_preload(components)? {
+            fs::write(settings.out_dir.join(PRELOAD_FILE), preload)?;
+        }
 
-            let source_file = settings
-                .out_dir
-                .join(format!("{}.php", config.module_name()));
-            fs::write(&source_file, library)?;
+        Ok(())
+    }
+}
+
+impl BindingGeneratorPHP {
+    /// The external formatter to run over the generated code, if we're formatting it at all.
+    fn external_formatter(&self, config: &Config) -> Option<(formatter::Formatter, Utf8PathBuf)> {
+        self.try_format_code
+            .then(|| formatter::find_external(config.formatter))
+            .flatten()
+    }
+
+    /// The files of a component's bindings, relative to the output directory. With
+    /// `pretty_print` the PHP goes through our own pretty-printer, for when there's no external
+    /// formatter to run afterwards.
+    fn bindings_files(
+        &self,
+        config: &Config,
+        ci: &ComponentInterface,
+        pretty_print: bool,
+    ) -> Result<Vec<(Utf8PathBuf, String)>> {
+        let Bindings {
+            library,
+            unsupported,
+        } = generate_bindings(config, ci)?;
+        let mut autoload = composer::Autoload::default();
+
+        let mut files = match config.layout {
+            OutputLayout::SingleFile => {
+                let file_name = Utf8PathBuf::from(format!("{}.php", config.module_name()?));
+                autoload.files.push(file_name.clone());
+                vec![(file_name, library)]
+            }
+            OutputLayout::Psr4 => {
+                let namespace = config.namespace()?;
+                let bindings = psr4::split(&library, &namespace)?;
+                for namespace in [namespace.as_str(), psr4::RUNTIME_NAMESPACE] {
+                    autoload
+                        .psr4
+                        .insert(namespace.to_string(), psr4::namespace_dir(namespace));
+                }
+                autoload.files = bindings.autoload_files;
+                bindings.files
+            }
+        };
+        if pretty_print {
+            for (_, contents) in &mut files {
+                *contents = formatter::pretty_print(contents);
+            }
+        }
+
+        match &config.composer {
+            Some(composer_config) => {
+                let json = composer::composer_json(composer_config, config, autoload)?;
+                files.push(("composer.json".into(), json));
+            }
+            None if config.layout == OutputLayout::Psr4 => {
+                for path in autoload.files {
+                    eprintln!(
+                        "note: `{path}` declares functions, add it to `autoload.files` in composer.json"
+                    );
+                }
+            }
+            None => {}
+        }
+
+        if config.backend.is_extension() {
+            files.extend(extension::files(config, ci)?);
+        }
+        if config.preload {
+            let module_name = config.module_name()?;
+            files.push((
+                format!("{module_name}.h").into(),
+                generate_header(config, ci)?,
+            ));
+        }
+
+        if !unsupported.is_empty() {
+            let report_name = format!("{}.unsupported.json", config.module_name()?);
+            eprintln!(
+                "warning: the `{}` bindings leave out what they don't support yet, see `{report_name}`:\n{}",
+                ci.namespace(),
+                unsupported.table()
+            );
+            let json = serde_json::to_string_pretty(&unsupported)? + "\n";
+            files.push((report_name.into(), json));
+        }
+        Ok(files)
+    }
+
+    /// Render the bindings and compare them with the files in the output directory, failing if
+    /// any are missing or different. Nothing is written to the output directory.
+    fn check_bindings(
+        &self,
+        settings: &GenerationSettings,
+        components: &[Component<Config>],
+    ) -> Result<()> {
+        let mut files = Vec::new();
+        for Component { ci, config, .. } in components {
+            let external_formatter = self.external_formatter(config);
+            let mut component_files = self.bindings_files(
+                config,
+                ci,
+                self.try_format_code && external_formatter.is_none(),
+            )?;
+            if let Some((formatter, command)) = external_formatter {
+                component_files =
+                    formatter::run_external_in_memory(formatter, &command, component_files)?;
+            }
+            files.extend(component_files);
+        }
+        if let Some(preload) = generate_preload(components)? {
+            files.push((PRELOAD_FILE.into(), preload));
         }
 
+        let mut stale = Vec::new();
+        for (path, contents) in files {
+            let path = settings.out_dir.join(path);
+            match fs::read_to_string(&path) {
+                Ok(on_disk) if on_disk == contents => {}
+                Ok(_) => stale.push(format!("`{path}` is out of date")),
+                Err(_) => stale.push(format!("`{path}` is missing")),
+            }
+        }
+        if !stale.is_empty() {
+            for message in &stale {
+                eprintln!("{message}");
+            }
+            bail!(
+                "the bindings in `{}` don't match the interface, regenerate them with `uniffi-bindgen-php generate`",
+                settings.out_dir
+            );
+        }
+        Ok(())
+    }
+
+    /// Write a `{module}.stub.php` for each component, whatever the layout.
+    fn write_stubs(
+        &self,
+        settings: &GenerationSettings,
+        components: &[Component<Config>],
+    ) -> Result<()> {
+        for Component { ci, config, .. } in components {
+            let mut stubs = generate_stubs(config, ci)?;
+            let external_formatter = self.external_formatter(config);
+            if self.try_format_code && external_formatter.is_none() {
+                stubs = formatter::pretty_print(&stubs);
+            }
+            let path = settings
+                .out_dir
+                .join(format!("{}.stub.php", config.module_name()?));
+            fs::write(&path, stubs)?;
+            if let Some((formatter, command)) = external_formatter {
+                formatter::run_external(formatter, &command, &[path]);
+            }
+        }
         Ok(())
     }
 }
 
+/// Write a `{module}.h` for each component.
+fn write_headers(settings: &GenerationSettings, components: &[Component<Config>]) -> Result<()> {
+    for Component { ci, config, .. } in components {
+        let header = generate_header(config, ci)?;
+        let path = settings
+            .out_dir
+            .join(format!("{}.h", config.module_name()?));
+        fs::write(path, header)?;
+    }
+    Ok(())
+}
+
 #[derive(Template)]
 #[template(escape = "none", path = "Types.php")]
 pub struct TypeRenderer<'a> {
-    php_config: &'a Config,
+    config: &'a Config,
+    features: PhpFeatures,
     ci: &'a ComponentInterface,
     include_once_names: RefCell<HashSet<String>>,
 }
 
 impl<'a> TypeRenderer<'a> {
-    fn new(php_config: &'a Config, ci: &'a ComponentInterface) -> Self {
+    fn new(config: &'a Config, ci: &'a ComponentInterface) -> Self {
         Self {
-            php_config,
+            config,
+            features: config.features(),
             ci,
             include_once_names: RefCell::new(HashSet::new()),
         }
     }
+
+    // Helper for the including a template, but only once.
+    //
+    // The first time this is called with a name it will return true, indicating that we should
+    // include the template.  Subsequent calls will return false.
+    fn include_once_check(&self, name: &str) -> bool {
+        self.include_once_names
+            .borrow_mut()
+            .insert(name.to_string())
+    }
 }
 
 #[derive(Template)]
@@ -218,76 +939,266 @@ impl<'a> TypeRenderer<'a> {
 pub struct PhpWrapper<'a> {
     ci: &'a ComponentInterface,
     config: Config,
+    features: PhpFeatures,
+    namespace: String,
+    /// The `FFI_SCOPE` to look for, when the FFI may have been preloaded.
+    preload_scope: Option<String>,
+    ffi_definitions: String,
+    /// The class of the object we make the FFI calls on.
+    ffi_class: String,
+    extension_name: String,
     type_helper_code: String,
 }
 
 impl<'a> PhpWrapper<'a> {
-    pub fn new(config: Config, ci: &'a ComponentInterface) -> Self {
+    pub fn new(config: Config, ci: &'a ComponentInterface) -> Result<Self> {
         let type_renderer = TypeRenderer::new(&config, ci);
-        let type_helper_code = type_renderer.render().expect("type rendering");
-        Self {
+        let type_helper_code = type_renderer
+            .render()
+            .context("failed to render PHP types")?;
+        let namespace = config.namespace()?;
+        Ok(Self {
             ci,
+            features: config.features(),
+            ffi_class: match config.backend {
+                Backend::Ffi => "\\FFI".into(),
+                Backend::Extension => format!("\\{namespace}\\{}", extension::NATIVE_CLASS),
+            },
+            extension_name: config.extension_name()?,
+            namespace,
+            preload_scope: config.preload.then(|| config.ffi_scope()).transpose()?,
+            ffi_definitions: config.ffi_definitions(ci)?,
             config,
             type_helper_code,
-        }
+        })
+    }
+
+    /// Whether any function or method is a stub throwing `UnsupportedException`.
+    pub fn has_unsupported(&self) -> bool {
+        OracleContext::has_unsupported_callables()
+    }
+
+    /// Whether any callback interface, or trait interface PHP can implement, has async methods.
+    pub fn has_async_callback_methods(&self) -> bool {
+        self.ci
+            .callback_interface_definitions()
+            .iter()
+            .any(|cbi| cbi.has_async_method())
+            || self.ci.object_definitions().iter().any(|obj| {
+                obj.has_callback_interface() && obj.methods().iter().any(|m| m.is_async())
+            })
+    }
+
+    pub fn initialization_fns(&self) -> Vec<String> {
+        self.ci
+            .iter_types()
+            .filter(|t| !OracleContext::is_omitted(t))
+            .map(|t| PHPCodeOracle.find(t))
+            .filter_map(|ct| ct.initialization_fn())
+            .collect()
+    }
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "stubs/wrapper.php")]
+pub struct StubsWrapper<'a> {
+    ci: &'a ComponentInterface,
+    config: Config,
+    features: PhpFeatures,
+    module_name: String,
+    namespace: String,
+}
+
+impl<'a> StubsWrapper<'a> {
+    pub fn new(config: Config, ci: &'a ComponentInterface) -> Result<Self> {
+        Ok(Self {
+            ci,
+            features: config.features(),
+            module_name: config.module_name()?,
+            namespace: config.namespace()?,
+            config,
+        })
+    }
+
+    /// Whether any function or method is a stub throwing `UnsupportedException`.
+    pub fn has_unsupported(&self) -> bool {
+        OracleContext::has_unsupported_callables()
+    }
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "FfiDefinitions.h")]
+pub struct FfiDefinitions {
+    definitions: Vec<FfiDefinition>,
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "Preload.php")]
+pub struct PreloadScript {
+    headers: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(escape = "none", path = "Header.h")]
+pub struct FfiHeader<'a> {
+    ci: &'a ComponentInterface,
+    ffi_definitions: String,
+    ffi_scope: String,
+    ffi_lib: String,
+}
+
+/// What we need to know about a type on top of `CodeType`.
+pub trait PhpCodeType: CodeType {
+    /// The type as written in PHPDoc, which unlike a native type can say what an array holds
+    /// or which ints are valid. Static analysers like PHPStan and Psalm check against this.
+    fn doc_type_label(&self) -> String {
+        self.type_label()
     }
 }
+
 #[derive(Clone)]
 pub struct PHPCodeOracle;
 
 impl PHPCodeOracle {
-    fn create_code_type(&self, type_: Type) -> Box<dyn CodeType> {
+    // Map `Type` instances to a `Box<dyn PhpCodeType>` for that type.
+    //
+    // There is a companion match in `templates/Types.php` which performs a similar function for the
+    // template code.
+    //
+    //   - When adding additional types here, make sure to also add a match arm to the `Types.php` template.
+    //   - To keep things manageable, let's try to limit ourselves to these 2 mega-matches
+    fn create_code_type(&self, type_: Type) -> Box<dyn PhpCodeType> {
         match type_ {
-            Type::UInt8 => todo!(),
-            Type::Int8 => todo!(),
-            Type::UInt16 => todo!(),
-            Type::Int16 => todo!(),
-            Type::UInt32 => todo!(),
-            Type::Int32 => todo!(),
-            Type::UInt64 => todo!(),
-            Type::Int64 => todo!(),
-            Type::Float32 => todo!(),
-            Type::Float64 => todo!(),
-            Type::Boolean => todo!(),
-            Type::String => todo!(),
-            Type::Bytes => todo!(),
-
-            Type::Timestamp => todo!(),
-            Type::Duration => todo!(),
-
-            Type::Enum { name, .. } => todo!(),
-            Type::Object { name, imp, .. } => todo!(),
-            Type::Record { name, .. } => todo!(),
-            Type::CallbackInterface { name, .. } => todo!(),
-            Type::Optional { inner_type } => todo!(),
-            Type::Sequence { inner_type } => todo!(),
+            Type::UInt8 => Box::new(primitives::UInt8CodeType),
+            Type::Int8 => Box::new(primitives::Int8CodeType),
+            Type::UInt16 => Box::new(primitives::UInt16CodeType),
+            Type::Int16 => Box::new(primitives::Int16CodeType),
+            Type::UInt32 => Box::new(primitives::UInt32CodeType),
+            Type::Int32 => Box::new(primitives::Int32CodeType),
+            Type::UInt64 => Box::new(primitives::UInt64CodeType),
+            Type::Int64 => Box::new(primitives::Int64CodeType),
+            Type::Float32 => Box::new(primitives::Float32CodeType),
+            Type::Float64 => Box::new(primitives::Float64CodeType),
+            Type::Boolean => Box::new(primitives::BooleanCodeType),
+            Type::String => Box::new(primitives::StringCodeType),
+            Type::Bytes => Box::new(primitives::BytesCodeType),
+
+            // `unsupported::Report` keeps these out of the bindings, and external types from
+            // another library: generation fails on them, or leaves out everything using them.
+            Type::Timestamp | Type::Duration => {
+                unreachable!("unsupported type {type_:?}, see `unsupported::Report`")
+            }
+            Type::External {
+                ref name,
+                ref module_path,
+                ..
+            } => match OracleContext::external_type(module_path, name) {
+                Some(names) => Box::new(external::ExternalCodeType::new(name.clone(), names)),
+                None => unreachable!("unsupported type {type_:?}, see `unsupported::Report`"),
+            },
+
+            Type::Enum { name, .. } => {
+                let is_int = OracleContext::is_int_enum(&name);
+                Box::new(enum_::EnumCodeType::new(name, is_int))
+            }
+            Type::Object { name, imp, .. } => Box::new(object::ObjectCodeType::new(name, imp)),
+            Type::Record { name, .. } => Box::new(record::RecordCodeType::new(name)),
+            Type::CallbackInterface { name, .. } => {
+                Box::new(callback_interface::CallbackInterfaceCodeType::new(name))
+            }
+            Type::Optional { inner_type } => {
+                Box::new(compounds::OptionalCodeType::new(*inner_type))
+            }
+            Type::Sequence { inner_type } => {
+                Box::new(compounds::SequenceCodeType::new(*inner_type))
+            }
             Type::Map {
                 key_type,
                 value_type,
-            } => todo!(),
-            Type::External { name, .. } => todo!(),
-            Type::Custom { name, .. } => todo!(),
+            } => Box::new(compounds::MapCodeType::new(*key_type, *value_type)),
+            Type::Custom { name, builtin, .. } => {
+                Box::new(custom::CustomCodeType::new(name, *builtin))
+            }
         }
     }
 
-    fn find(&self, type_: &Type) -> Box<dyn CodeType> {
+    fn find(&self, type_: &Type) -> Box<dyn PhpCodeType> {
         self.create_code_type(type_.clone())
     }
 
     fn class_name(&self, nm: &str) -> String {
-        nm.to_string().to_upper_camel_case()
+        let name = OracleContext::renamed(nm).unwrap_or_else(|| nm.to_upper_camel_case());
+        php_escape(name, NameContext::Class)
     }
 
     fn fn_name(&self, nm: &str) -> String {
-        nm.to_string().to_lower_camel_case()
+        let name =
+            OracleContext::renamed(nm).unwrap_or_else(|| OracleContext::naming().methods.apply(nm));
+        php_escape(name, NameContext::Function)
+    }
+
+    // Top-level functions as methods of the facade class; members of types are named by
+    // `member_method_name`.
+    fn method_name(&self, nm: &str) -> String {
+        let name =
+            OracleContext::renamed(nm).unwrap_or_else(|| OracleContext::naming().methods.apply(nm));
+        php_escape(name, NameContext::Method)
+    }
+
+    /// The name of a method or constructor of the type `parent`.
+    fn member_method_name(&self, nm: &str, parent: &str) -> String {
+        let name = OracleContext::renamed(&format!("{parent}.{nm}"))
+            .unwrap_or_else(|| OracleContext::naming().methods.apply(nm));
+        php_escape(name, NameContext::Method)
     }
 
     fn var_name(&self, nm: &str) -> String {
-        nm.to_string().to_lower_camel_case()
+        let properties = OracleContext::naming().properties;
+        php_escape(properties.apply(nm), NameContext::Variable)
+    }
+
+    /// The name of a field of the record `parent`.
+    fn field_name(&self, nm: &str, parent: &str) -> String {
+        let name = OracleContext::renamed(&format!("{parent}.{nm}"))
+            .unwrap_or_else(|| OracleContext::naming().properties.apply(nm));
+        php_escape(name, NameContext::Variable)
+    }
+
+    /// The property name of a field of a data enum or error variant. Error variants extend
+    /// `\Exception`, whose properties they can't redeclare.
+    fn variant_field_name(&self, nm: &str, in_exception: bool) -> String {
+        let properties = OracleContext::naming().properties;
+        let context = if in_exception {
+            NameContext::ExceptionProperty
+        } else {
+            NameContext::Variable
+        };
+        php_escape(properties.apply(nm), context)
+    }
+
+    /// The name of a variant of the flat enum `parent`.
+    fn enum_variant_name(&self, nm: &str, parent: &str) -> String {
+        let name = OracleContext::renamed(&format!("{parent}.{nm}"))
+            .unwrap_or_else(|| OracleContext::variant_case().apply(nm));
+        php_escape(name, NameContext::Constant)
+    }
+
+    // Variants of data enums and errors become subclasses named `{Enum}{Variant}`, the variant
+    // alone is never the whole class name so it doesn't need escaping.
+    fn variant_class_name(&self, nm: &str, parent: &str) -> String {
+        OracleContext::renamed(&format!("{parent}.{nm}"))
+            .unwrap_or_else(|| nm.to_upper_camel_case())
     }
 
-    fn enum_variant_name(&self, nm: &str) -> String {
-        nm.to_string().to_lower_camel_case()
+    // Getters are always prefixed, so they can't clash with reserved method names, but those of
+    // error variants can clash with the getters of `\Exception`.
+    fn getter_name(&self, nm: &str, in_exception: bool) -> String {
+        let getter = OracleContext::naming().methods.getter(nm);
+        if in_exception {
+            php_escape(getter, NameContext::ExceptionGetter)
+        } else {
+            getter
+        }
     }
 
     fn ffi_callback_name(&self, nm: &str) -> String {
@@ -298,70 +1209,115 @@ impl PHPCodeOracle {
         format!("Uniffi{}", nm.to_upper_camel_case())
     }
 
-    fn if_guard_name(&self, nm: &str) -> String {
-        format!("UNIFFI_FFIDEF_{}", nm.to_shouty_snake_case())
-    }
-
+    /// The C type used for an `FfiType` in the `FFI::cdef()` declarations.
     fn ffi_type_label(&self, ffi_type: &FfiType) -> String {
         match ffi_type {
-            FfiType::Int8 => "Int8".into(),
-            FfiType::UInt8 => "UInt8".into(),
-            FfiType::Int16 => "Int16".into(),
-            FfiType::UInt16 => "UInt16".into(),
-            FfiType::Int32 => "Int32".into(),
-            FfiType::UInt32 => "UInt32".into(),
-            FfiType::Int64 => "Int64".into(),
-            FfiType::UInt64 => "UInt64".into(),
-            FfiType::Float32 => "Float".into(),
-            FfiType::Float64 => "Double".into(),
-            FfiType::Handle => "UInt64".into(),
-            FfiType::RustArcPtr(_) => "UnsafeMutableRawPointer".into(),
+            FfiType::Int8 => "int8_t".into(),
+            FfiType::UInt8 => "uint8_t".into(),
+            FfiType::Int16 => "int16_t".into(),
+            FfiType::UInt16 => "uint16_t".into(),
+            FfiType::Int32 => "int32_t".into(),
+            FfiType::UInt32 => "uint32_t".into(),
+            FfiType::Int64 => "int64_t".into(),
+            FfiType::UInt64 => "uint64_t".into(),
+            FfiType::Float32 => "float".into(),
+            FfiType::Float64 => "double".into(),
+            FfiType::Handle => "uint64_t".into(),
+            FfiType::RustArcPtr(_) => "void*".into(),
             FfiType::RustBuffer(_) => "RustBuffer".into(),
             FfiType::RustCallStatus => "RustCallStatus".into(),
             FfiType::ForeignBytes => "ForeignBytes".into(),
-            FfiType::Callback(name) => format!("@escaping {}", self.ffi_callback_name(name)),
+            FfiType::Callback(name) => self.ffi_callback_name(name),
             FfiType::Struct(name) => self.ffi_struct_name(name),
-            FfiType::Reference(inner) => {
-                format!("UnsafeMutablePointer<{}>", self.ffi_type_label(inner))
-            }
-            FfiType::VoidPointer => "UnsafeMutableRawPointer".into(),
-        }
-    }
-
-    fn ffi_default_value(&self, return_type: Option<&FfiType>) -> String {
-        match return_type {
-            Some(t) => match t {
-                FfiType::UInt8
-                | FfiType::Int8
-                | FfiType::UInt16
-                | FfiType::Int16
-                | FfiType::UInt32
-                | FfiType::Int32
-                | FfiType::UInt64
-                | FfiType::Int64 => "0".to_owned(),
-                FfiType::Float32 | FfiType::Float64 => "0.0".to_owned(),
-                FfiType::RustArcPtr(_) => "nil".to_owned(),
-                FfiType::RustBuffer(_) => "RustBuffer.empty()".to_owned(),
-                _ => unimplemented!("FFI return type: {t:?}"),
-            },
-            None => "0".to_owned(),
+            FfiType::Reference(inner) => format!("{}*", self.ffi_type_label(inner)),
+            FfiType::VoidPointer => "void*".into(),
         }
     }
 
     fn object_names(&self, obj: &Object) -> (String, String) {
-        let class_name = self.class_name(obj.name());
+        let class_name =
+            OracleContext::renamed(obj.name()).unwrap_or_else(|| obj.name().to_upper_camel_case());
         if obj.has_callback_interface() {
             let impl_name = format!("{class_name}Impl");
-            (class_name, impl_name)
+            (php_escape(class_name, NameContext::Class), impl_name)
+        } else {
+            (
+                format!("{class_name}Interface"),
+                php_escape(class_name, NameContext::Class),
+            )
+        }
+    }
+}
+
+/// A function argument or record field, which may have a default.
+pub trait Parameter {
+    /// The PHP literal of the default, if there's one.
+    fn php_default(&self) -> Option<String>;
+}
+
+impl Parameter for Argument {
+    fn php_default(&self) -> Option<String> {
+        self.default_value()
+            .map(|literal| PHPCodeOracle.find(&self.as_type()).literal(literal))
+    }
+}
+
+impl Parameter for Field {
+    fn php_default(&self) -> Option<String> {
+        self.default_value()
+            .map(|literal| PHPCodeOracle.find(&self.as_type()).literal(literal))
+    }
+}
+
+impl<T: Parameter> Parameter for &T {
+    fn php_default(&self) -> Option<String> {
+        (**self).php_default()
+    }
+}
+
+/// Whether the PHPDoc type of `as_type` says more than its native type.
+fn refines_type(as_type: &impl AsType) -> bool {
+    let code_type = PHPCodeOracle.find(&as_type.as_type());
+    code_type.doc_type_label() != code_type.type_label()
+}
+
+/// A `/** ... */` block holding `docstring` and then `tags`, indented by `spaces`. Empty when
+/// there's nothing to put in it.
+fn phpdoc(docstring: Option<&str>, tags: &[String], spaces: usize) -> String {
+    let mut lines: Vec<String> = docstring
+        .map(|docstring| {
+            // `*/` would end the comment early.
+            textwrap::dedent(docstring)
+                .trim()
+                .replace("*/", "*\\/")
+                .lines()
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    if !lines.is_empty() && !tags.is_empty() {
+        lines.push(String::new());
+    }
+    lines.extend(tags.iter().cloned());
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let indent = " ".repeat(spaces);
+    let mut block = format!("{indent}/**\n");
+    for line in lines {
+        if line.is_empty() {
+            block.push_str(&format!("{indent} *\n"));
         } else {
-            (format!("{class_name}Protocol"), class_name)
+            block.push_str(&format!("{indent} * {line}\n"));
         }
     }
+    block.push_str(&format!("{indent} */"));
+    block
 }
 
 pub mod filters {
-    use uniffi_bindgen::{backend::Literal, interface::Enum};
-    use uniffi_meta::{AsType, LiteralMetadata};
+    use uniffi_meta::LiteralMetadata;
 
     use super::*;
 
@@ -373,10 +1329,14 @@ pub mod filters {
         Ok(oracle().find(&as_type.as_type()).type_label())
     }
 
+    pub fn doc_type_name(as_type: &impl AsType) -> Result<String, askama::Error> {
+        Ok(oracle().find(&as_type.as_type()).doc_type_label())
+    }
+
     pub fn return_type_name(as_type: Option<&impl AsType>) -> Result<String, askama::Error> {
         Ok(match as_type {
             Some(as_type) => oracle().find(&as_type.as_type()).type_label(),
-            None => "()".to_owned(),
+            None => "void".to_owned(),
         })
     }
 
@@ -397,31 +1357,69 @@ pub mod filters {
     }
 
     pub fn lower_fn(as_type: &impl AsType) -> Result<String, askama::Error> {
-        Ok(oracle().find(&as_type.as_type()).lower())
+        Ok(format!(
+            "{}::lower",
+            oracle().find(&as_type.as_type()).ffi_converter_name()
+        ))
     }
 
     pub fn write_fn(as_type: &impl AsType) -> Result<String, askama::Error> {
-        Ok(oracle().find(&as_type.as_type()).write())
+        Ok(format!(
+            "{}::write",
+            oracle().find(&as_type.as_type()).ffi_converter_name()
+        ))
+    }
+
+    /// `$key` as the map key type `as_type` expects to write it. PHP turns array keys which are
+    /// numeric strings into ints, so string keys read from Rust may have to be cast back.
+    pub fn map_key(as_type: &impl AsType) -> Result<String, askama::Error> {
+        Ok(
+            match oracle().find(&as_type.as_type()).type_label().as_str() {
+                "string" => "(string) $key".into(),
+                _ => "$key".into(),
+            },
+        )
     }
 
     pub fn lift_fn(as_type: &impl AsType) -> Result<String, askama::Error> {
-        Ok(oracle().find(&as_type.as_type()).lift())
+        Ok(format!(
+            "{}::lift",
+            oracle().find(&as_type.as_type()).ffi_converter_name()
+        ))
     }
 
     pub fn read_fn(as_type: &impl AsType) -> Result<String, askama::Error> {
-        Ok(oracle().find(&as_type.as_type()).read())
+        Ok(format!(
+            "{}::read",
+            oracle().find(&as_type.as_type()).ffi_converter_name()
+        ))
     }
 
-    pub fn literal_php(literal: &Literal, as_type: &impl AsType) -> Result<String, askama::Error> {
-        Ok(oracle().find(&as_type.as_type()).literal(literal))
+    /// ` = <default>` for the parameter at `index`, if it has a default and so do all the ones
+    /// after it. PHP ignores the default of a parameter before a required one, and deprecates it.
+    pub fn param_default<P: Parameter>(
+        params: impl AsRef<[P]>,
+        index: &usize,
+    ) -> Result<String, askama::Error> {
+        let params = params.as_ref();
+        let default = params[*index].php_default();
+        let trailing = params[*index..].iter().all(|p| p.php_default().is_some());
+        Ok(match default {
+            Some(default) if trailing => format!(" = {default}"),
+            _ => String::new(),
+        })
     }
 
     pub fn variant_discr_literal(e: &Enum, index: &usize) -> Result<String, askama::Error> {
-        let literal = e.variant_discr(*index).expect("invalid index");
+        let literal = e
+            .variant_discr(*index)
+            .map_err(|err| askama::Error::Custom(err.into()))?;
         match literal {
             LiteralMetadata::UInt(v, _, _) => Ok(v.to_string()),
             LiteralMetadata::Int(v, _, _) => Ok(v.to_string()),
-            _ => unreachable!("expected an UInt!"),
+            _ => Err(askama::Error::Custom(
+                format!("`{}` has a discriminant which isn't an integer", e.name()).into(),
+            )),
         }
     }
 
@@ -429,58 +1427,109 @@ pub mod filters {
         Ok(oracle().ffi_type_label(ffi_type))
     }
 
-    pub fn ffi_default_value(return_type: Option<FfiType>) -> Result<String, askama::Error> {
-        Ok(oracle().ffi_default_value(return_type.as_ref()))
-    }
-
-    pub fn header_ffi_type_name(ffi_type: &FfiType) -> Result<String, askama::Error> {
-        Ok(match ffi_type {
-            FfiType::Int8 => "int8_t".into(),
-            FfiType::UInt8 => "uint8_t".into(),
-            FfiType::Int16 => "int16_t".into(),
-            FfiType::UInt16 => "uint16_t".into(),
-            FfiType::Int32 => "int32_t".into(),
-            FfiType::UInt32 => "uint32_t".into(),
-            FfiType::Int64 => "int64_t".into(),
-            FfiType::UInt64 => "uint64_t".into(),
-            FfiType::Float32 => "float".into(),
-            FfiType::Float64 => "double".into(),
-            FfiType::Handle => "uint64_t".into(),
-            FfiType::RustArcPtr(_) => "void*_Nonnull".into(),
-            FfiType::RustBuffer(_) => "RustBuffer".into(),
-            FfiType::RustCallStatus => "RustCallStatus".into(),
-            FfiType::ForeignBytes => "ForeignBytes".into(),
-            FfiType::Callback(name) => {
-                format!("{} _Nonnull", PHPCodeOracle.ffi_callback_name(name))
-            }
-            FfiType::Struct(name) => PHPCodeOracle.ffi_struct_name(name),
-            FfiType::Reference(inner) => format!("{}* _Nonnull", header_ffi_type_name(inner)?),
-            FfiType::VoidPointer => "void* _Nonnull".into(),
-        })
-    }
-
     pub fn class_name(nm: &str) -> Result<String, askama::Error> {
         Ok(oracle().class_name(nm))
     }
 
     pub fn fn_name(nm: &str) -> Result<String, askama::Error> {
-        Ok(quote_general_keyword(oracle().fn_name(nm)))
+        Ok(oracle().fn_name(nm))
+    }
+
+    pub fn method_name(nm: &str) -> Result<String, askama::Error> {
+        Ok(oracle().method_name(nm))
     }
 
     pub fn var_name(nm: &str) -> Result<String, askama::Error> {
-        Ok(quote_general_keyword(oracle().var_name(nm)))
+        Ok(oracle().var_name(nm))
     }
 
     pub fn arg_name(nm: &str) -> Result<String, askama::Error> {
-        Ok(quote_arg_keyword(oracle().var_name(nm)))
+        Ok(oracle().var_name(nm))
+    }
+
+    pub fn member_method_name(nm: &str, parent: &str) -> Result<String, askama::Error> {
+        Ok(oracle().member_method_name(nm, parent))
+    }
+
+    pub fn field_name(nm: &str, parent: &str) -> Result<String, askama::Error> {
+        Ok(oracle().field_name(nm, parent))
+    }
+
+    pub fn variant_field_name(nm: &str, in_exception: &bool) -> Result<String, askama::Error> {
+        Ok(oracle().variant_field_name(nm, *in_exception))
+    }
+
+    pub fn enum_variant_php_quoted(nm: &str, parent: &str) -> Result<String, askama::Error> {
+        Ok(oracle().enum_variant_name(nm, parent))
+    }
+
+    pub fn variant_class_name(nm: &str, parent: &str) -> Result<String, askama::Error> {
+        Ok(oracle().variant_class_name(nm, parent))
+    }
+
+    /// Whether `type_` has no place in the bindings, being (or containing) an excluded or
+    /// unsupported type.
+    pub fn is_omitted(type_: &Type) -> Result<bool, askama::Error> {
+        Ok(OracleContext::is_omitted(type_))
+    }
+
+    /// The message of the `UnsupportedException` thrown in place of calling `nm`, a member of
+    /// `parent` or, if that's empty, a top-level function. `None` if the bindings can call it.
+    pub fn unsupported(nm: &str, parent: &str) -> Result<Option<String>, askama::Error> {
+        let key = if parent.is_empty() {
+            nm.to_string()
+        } else {
+            format!("{parent}.{nm}")
+        };
+        Ok(OracleContext::unsupported_reason(&key)
+            .map(|reason| format!("`{key}` isn't supported by the PHP bindings ({reason})")))
+    }
+
+    /// `s` as a PHP string literal.
+    pub fn php_string(s: &str) -> Result<String, askama::Error> {
+        Ok(format!(
+            "'{}'",
+            s.replace('\\', "\\\\").replace('\'', "\\'")
+        ))
+    }
+
+    pub fn included_functions(functions: &[Function]) -> Result<Vec<&Function>, askama::Error> {
+        Ok(functions
+            .iter()
+            .filter(|func| !OracleContext::is_excluded(func.name()))
+            .collect())
+    }
+
+    /// The methods of the type `parent` which aren't excluded.
+    pub fn included_methods<'a>(
+        methods: Vec<&'a Method>,
+        parent: &str,
+    ) -> Result<Vec<&'a Method>, askama::Error> {
+        Ok(methods
+            .into_iter()
+            .filter(|m| !OracleContext::is_excluded(&format!("{parent}.{}", m.name())))
+            .collect())
+    }
+
+    pub fn included_constructors<'a>(
+        constructors: Vec<&'a Constructor>,
+        parent: &str,
+    ) -> Result<Vec<&'a Constructor>, askama::Error> {
+        Ok(constructors
+            .into_iter()
+            .filter(|c| !OracleContext::is_excluded(&format!("{parent}.{}", c.name())))
+            .collect())
     }
 
-    pub fn enum_variant_php_quoted(nm: &str) -> Result<String, askama::Error> {
-        Ok(quote_general_keyword(oracle().enum_variant_name(nm)))
+    pub fn included_constructor<'a>(
+        cons: Option<&'a Constructor>,
+        parent: &str,
+    ) -> Result<Option<&'a Constructor>, askama::Error> {
+        Ok(cons.filter(|c| !OracleContext::is_excluded(&format!("{parent}.{}", c.name()))))
     }
 
-    pub fn error_variant_php_quoted(nm: &str) -> Result<String, askama::Error> {
-        Ok(quote_general_keyword(oracle().class_name(nm)))
+    pub fn getter_name(nm: &str, in_exception: &bool) -> Result<String, askama::Error> {
+        Ok(oracle().getter_name(nm, *in_exception))
     }
 
     pub fn ffi_callback_name(nm: &str) -> Result<String, askama::Error> {
@@ -491,16 +1540,130 @@ pub mod filters {
         Ok(oracle().ffi_struct_name(nm))
     }
 
-    pub fn if_guard_name(nm: &str) -> Result<String, askama::Error> {
-        Ok(oracle().if_guard_name(nm))
+    pub fn docstring(docstring: &str, spaces: &i32) -> Result<String, askama::Error> {
+        let spaces = usize::try_from(*spaces).unwrap_or_default();
+        Ok(phpdoc(Some(docstring), &[], spaces))
     }
 
-    pub fn docstring(docstring: &str, spaces: &i32) -> Result<String, askama::Error> {
-        let middle = textwrap::indent(&textwrap::dedent(docstring), " * ");
-        let wrapped = format!("/**\n{middle}\n */");
+    /// The PHPDoc of a stub standing in for a function, method or constructor the bindings can't
+    /// call, with the exception's `message`.
+    pub fn unsupported_docstring(
+        docstring: &Option<&str>,
+        message: &str,
+        spaces: &i32,
+    ) -> Result<String, askama::Error> {
+        let spaces = usize::try_from(*spaces).unwrap_or_default();
+        let tags = [
+            format!("@deprecated {message}"),
+            "@throws UnsupportedException".to_string(),
+        ];
+        Ok(phpdoc(*docstring, &tags, spaces))
+    }
+
+    /// The PHPDoc of a function, method or constructor: its docstring, if any, followed by
+    /// `@param`, `@return` and `@throws` tags. Functions with no docstring, nothing to throw and
+    /// only types PHP can express natively get no PHPDoc, their signature says it all.
+    pub fn fn_docstring(
+        callable: &impl Callable,
+        docstring: &Option<&str>,
+        config: &Config,
+        returns: &bool,
+        spaces: &i32,
+    ) -> Result<String, askama::Error> {
+        let throws_type = callable.throws_type();
+        let awaitable = config
+            .async_runtime
+            .awaitable_type()
+            .filter(|_| callable.is_async());
+        let return_type = callable.return_type().filter(|_| *returns);
+        let refines_types = callable.arguments().iter().any(refines_type)
+            || return_type.as_ref().is_some_and(refines_type)
+            || (*returns && awaitable.is_some());
+        if docstring.is_none() && throws_type.is_none() && !refines_types {
+            return Ok(String::new());
+        }
+        let mut tags = Vec::new();
+        for arg in callable.arguments() {
+            tags.push(format!(
+                "@param {} ${}",
+                doc_type_name(arg)?,
+                arg_name(arg.name())?
+            ));
+        }
+        if awaitable.is_some() && config.async_runtime == AsyncRuntime::Amp {
+            tags.push("@param ?\\Amp\\Cancellation $cancellation".to_string());
+        }
+        if *returns {
+            let result = match &return_type {
+                Some(return_type) => Some(doc_type_name(return_type)?),
+                None => None,
+            };
+            match (awaitable, result) {
+                (Some(awaitable), result) => tags.push(format!(
+                    "@return {awaitable}<{}>",
+                    result.as_deref().unwrap_or("null")
+                )),
+                (None, Some(result)) => tags.push(format!("@return {result}")),
+                (None, None) => {}
+            }
+        }
+        if let Some(throws_type) = throws_type {
+            tags.push(format!("@throws {}", type_name(&throws_type)?));
+        }
+        let spaces = usize::try_from(*spaces).unwrap_or_default();
+        Ok(phpdoc(*docstring, &tags, spaces))
+    }
+
+    /// The PHPDoc of a property: its docstring, if any, and a `@var` tag when PHP's own type
+    /// doesn't say it all.
+    pub fn var_docstring(
+        as_type: &impl AsType,
+        docstring: &Option<&str>,
+        spaces: &i32,
+    ) -> Result<String, askama::Error> {
+        let mut tags = Vec::new();
+        if refines_type(as_type) {
+            tags.push(format!("@var {}", doc_type_name(as_type)?));
+        }
+        let spaces = usize::try_from(*spaces).unwrap_or_default();
+        Ok(phpdoc(*docstring, &tags, spaces))
+    }
 
+    /// The PHPDoc of a getter, when PHP's own return type doesn't say it all.
+    pub fn getter_docstring(as_type: &impl AsType, spaces: &i32) -> Result<String, askama::Error> {
+        if !refines_type(as_type) {
+            return Ok(String::new());
+        }
+        let tags = [format!("@return {}", doc_type_name(as_type)?)];
+        let spaces = usize::try_from(*spaces).unwrap_or_default();
+        Ok(phpdoc(None, &tags, spaces))
+    }
+
+    /// `@param` tags for the fields of a record or variant passed to its constructor, when
+    /// PHP's own types don't say it all. Nameless fields are named by position, and `parent`
+    /// is the record, if any. `in_exception` is set for the variants of errors.
+    pub fn fields_docstring(
+        fields: &[Field],
+        parent: &str,
+        in_exception: &bool,
+        spaces: &i32,
+    ) -> Result<String, askama::Error> {
+        let mut tags = Vec::new();
+        for (i, field) in fields.iter().enumerate() {
+            if !refines_type(field) {
+                continue;
+            }
+            let name = if field.name().is_empty() {
+                format!("v{i}")
+            } else if parent.is_empty() {
+                oracle().variant_field_name(field.name(), *in_exception)
+            } else {
+                oracle().field_name(field.name(), parent)
+            };
+            tags.push(format!("@param {} ${name}", doc_type_name(field)?));
+        }
         let spaces = usize::try_from(*spaces).unwrap_or_default();
-        Ok(textwrap::indent(&wrapped, &" ".repeat(spaces)))
+        Ok(phpdoc(None, &tags, spaces))
     }
 
     pub fn object_names(obj: &Object) -> Result<(String, String), askama::Error> {
@@ -0,0 +1,355 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `[bindings.php.naming]` table, choosing how the names from Rust are cased in PHP, and
+//! the escaping of names PHP reserves.
+
+use std::{collections::HashSet, sync::Mutex};
+
+use heck::{ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Naming {
+    /// Functions and methods.
+    #[serde(default)]
+    pub methods: MemberCase,
+    /// Properties, and the arguments which may be passed by name.
+    #[serde(default)]
+    pub properties: MemberCase,
+    /// The cases of native enums.
+    #[serde(default)]
+    pub enum_cases: ConstantCase,
+    /// Class constants, which stand in for flat enums before PHP 8.1.
+    #[serde(default)]
+    pub constants: ConstantCase,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MemberCase {
+    #[default]
+    #[serde(rename = "camelCase")]
+    Camel,
+    #[serde(rename = "snake_case")]
+    Snake,
+}
+
+impl MemberCase {
+    pub fn apply(&self, nm: &str) -> String {
+        match self {
+            MemberCase::Camel => nm.to_lower_camel_case(),
+            MemberCase::Snake => nm.to_snake_case(),
+        }
+    }
+
+    /// The getter of a property called `nm`.
+    pub fn getter(&self, nm: &str) -> String {
+        match self {
+            MemberCase::Camel => format!("get{}", nm.to_upper_camel_case()),
+            MemberCase::Snake => format!("get_{}", nm.to_snake_case()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstantCase {
+    /// What we've always generated, kept as the default so upgrading doesn't rename anything.
+    #[default]
+    #[serde(rename = "camelCase")]
+    Camel,
+    #[serde(rename = "UpperCamelCase")]
+    UpperCamel,
+    #[serde(rename = "SHOUTY_SNAKE_CASE")]
+    ShoutySnake,
+}
+
+impl ConstantCase {
+    pub fn apply(&self, nm: &str) -> String {
+        match self {
+            ConstantCase::Camel => nm.to_lower_camel_case(),
+            ConstantCase::UpperCamel => nm.to_upper_camel_case(),
+            ConstantCase::ShoutySnake => nm.to_shouty_snake_case(),
+        }
+    }
+}
+
+// PHP's reserved keywords, see https://www.php.net/manual/en/reserved.keywords.php
+// PHP compares these case-insensitively, so they're all lowercase here.
+static KEYWORDS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "__halt_compiler",
+        "abstract",
+        "and",
+        "array",
+        "as",
+        "break",
+        "callable",
+        "case",
+        "catch",
+        "class",
+        "clone",
+        "const",
+        "continue",
+        "declare",
+        "default",
+        "die",
+        "do",
+        "echo",
+        "else",
+        "elseif",
+        "empty",
+        "enddeclare",
+        "endfor",
+        "endforeach",
+        "endif",
+        "endswitch",
+        "endwhile",
+        "eval",
+        "exit",
+        "extends",
+        "final",
+        "finally",
+        "fn",
+        "for",
+        "foreach",
+        "function",
+        "global",
+        "goto",
+        "if",
+        "implements",
+        "include",
+        "include_once",
+        "instanceof",
+        "insteadof",
+        "interface",
+        "isset",
+        "list",
+        "match",
+        "namespace",
+        "new",
+        "or",
+        "print",
+        "private",
+        "protected",
+        "public",
+        "readonly",
+        "require",
+        "require_once",
+        "return",
+        "static",
+        "switch",
+        "throw",
+        "trait",
+        "try",
+        "unset",
+        "use",
+        "var",
+        "while",
+        "xor",
+        "yield",
+    ]
+    .into_iter()
+    .collect()
+});
+
+// Names which aren't keywords but still can't name a class, interface or enum,
+// see https://www.php.net/manual/en/reserved.other-reserved-words.php
+static RESERVED_CLASS_NAMES: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "bool", "enum", "false", "float", "int", "iterable", "mixed", "never", "null", "numeric",
+        "object", "parent", "resource", "self", "string", "true", "void",
+    ]
+    .into_iter()
+    .collect()
+});
+
+// The final methods of `\Exception`, which the getters of error variants can't override.
+static EXCEPTION_METHODS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
+    [
+        "getcode",
+        "getfile",
+        "getline",
+        "getmessage",
+        "getprevious",
+        "gettrace",
+        "gettraceasstring",
+    ]
+    .into_iter()
+    .collect()
+});
+
+/// Where a name is used in the generated PHP, which decides the words it can't be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NameContext {
+    /// Classes, interfaces and enums.
+    Class,
+    /// Top-level functions.
+    Function,
+    /// Methods, which may be keywords but not start with `__`, PHP reserves those for magic methods.
+    Method,
+    /// Arguments, variables and properties; only `$this` is off limits.
+    Variable,
+    /// Class constants and enum cases, which may be anything but `class`.
+    Constant,
+    /// Properties of error variants, which can't redeclare those `\Exception` has.
+    ExceptionProperty,
+    /// Getters of error variants, which can't override the final methods of `\Exception`.
+    ExceptionGetter,
+}
+
+impl NameContext {
+    fn is_reserved(&self, nm: &str) -> bool {
+        let lower = nm.to_ascii_lowercase();
+        match self {
+            NameContext::Class => {
+                KEYWORDS.contains(lower.as_str()) || RESERVED_CLASS_NAMES.contains(lower.as_str())
+            }
+            NameContext::Function => KEYWORDS.contains(lower.as_str()),
+            NameContext::Method => nm.starts_with("__"),
+            NameContext::Variable => nm == "this",
+            NameContext::Constant => lower == "class",
+            NameContext::ExceptionProperty => {
+                matches!(nm, "this" | "message" | "code" | "file" | "line")
+            }
+            NameContext::ExceptionGetter => EXCEPTION_METHODS.contains(lower.as_str()),
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match self {
+            NameContext::Class => "class name",
+            NameContext::Function => "function name",
+            NameContext::Method => "method name",
+            NameContext::Variable => "variable name",
+            NameContext::Constant => "constant name",
+            NameContext::ExceptionProperty => "exception property name",
+            NameContext::ExceptionGetter => "exception method name",
+        }
+    }
+}
+
+// The renames we've already warned about, so each one is only reported once.
+static WARNED_RENAMES: Lazy<Mutex<HashSet<(NameContext, String)>>> =
+    Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Make `nm` usable in the given context, by appending `_` if PHP reserves it there.
+///
+/// Renames are reported on stderr, once per name and context.
+pub fn php_escape(nm: String, context: NameContext) -> String {
+    if !context.is_reserved(&nm) {
+        return nm;
+    }
+    let renamed = format!("{nm}_");
+    let first_time = WARNED_RENAMES.lock().unwrap().insert((context, nm.clone()));
+    if first_time {
+        eprintln!(
+            "warning: `{nm}` is reserved as a PHP {}, renamed to `{renamed}`",
+            context.describe()
+        );
+    }
+    renamed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_cases() {
+        assert_eq!(MemberCase::Camel.apply("get_user_id"), "getUserId");
+        assert_eq!(MemberCase::Snake.apply("getUserId"), "get_user_id");
+        assert_eq!(MemberCase::Camel.getter("user_id"), "getUserId");
+        assert_eq!(MemberCase::Snake.getter("userId"), "get_user_id");
+    }
+
+    #[test]
+    fn constant_cases() {
+        assert_eq!(ConstantCase::Camel.apply("DARK_RED"), "darkRed");
+        assert_eq!(ConstantCase::UpperCamel.apply("dark_red"), "DarkRed");
+        assert_eq!(ConstantCase::ShoutySnake.apply("DarkRed"), "DARK_RED");
+    }
+
+    #[test]
+    fn naming_table() {
+        let naming: Naming = toml::from_str(
+            r#"
+            methods = "snake_case"
+            constants = "SHOUTY_SNAKE_CASE"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(naming.methods, MemberCase::Snake);
+        assert_eq!(naming.properties, MemberCase::Camel);
+        assert_eq!(naming.enum_cases, ConstantCase::Camel);
+        assert_eq!(naming.constants, ConstantCase::ShoutySnake);
+
+        let unknown = toml::from_str::<Naming>(r#"methods = "kebab-case""#);
+        assert!(unknown.is_err());
+    }
+
+    fn escape(nm: &str, context: NameContext) -> String {
+        php_escape(nm.to_string(), context)
+    }
+
+    #[test]
+    fn keywords_are_escaped_case_insensitively() {
+        assert_eq!(escape("list", NameContext::Function), "list_");
+        assert_eq!(escape("Class", NameContext::Class), "Class_");
+        assert_eq!(escape("FOREACH", NameContext::Function), "FOREACH_");
+        assert_eq!(escape("listing", NameContext::Function), "listing");
+    }
+
+    #[test]
+    fn reserved_class_names_only_apply_to_classes() {
+        assert_eq!(escape("String", NameContext::Class), "String_");
+        assert_eq!(escape("Iterable", NameContext::Class), "Iterable_");
+        assert_eq!(escape("string", NameContext::Function), "string");
+        assert_eq!(escape("Strings", NameContext::Class), "Strings");
+    }
+
+    #[test]
+    fn methods_may_be_keywords_but_not_magic() {
+        assert_eq!(escape("list", NameContext::Method), "list");
+        assert_eq!(escape("__construct", NameContext::Method), "__construct_");
+        assert_eq!(escape("_private", NameContext::Method), "_private");
+    }
+
+    #[test]
+    fn variables_and_constants() {
+        assert_eq!(escape("this", NameContext::Variable), "this_");
+        assert_eq!(escape("class", NameContext::Variable), "class");
+        assert_eq!(escape("This", NameContext::Variable), "This");
+        assert_eq!(escape("CLASS", NameContext::Constant), "CLASS_");
+        assert_eq!(escape("list", NameContext::Constant), "list");
+    }
+
+    #[test]
+    fn exception_members() {
+        assert_eq!(
+            escape("message", NameContext::ExceptionProperty),
+            "message_"
+        );
+        assert_eq!(escape("line", NameContext::ExceptionProperty), "line_");
+        assert_eq!(escape("this", NameContext::ExceptionProperty), "this_");
+        assert_eq!(escape("Message", NameContext::ExceptionProperty), "Message");
+        assert_eq!(
+            escape("getMessage", NameContext::ExceptionGetter),
+            "getMessage_"
+        );
+        assert_eq!(
+            escape("get_trace", NameContext::ExceptionGetter),
+            "get_trace"
+        );
+        assert_eq!(escape("getValue", NameContext::ExceptionGetter), "getValue");
+    }
+
+    #[test]
+    fn renames_are_remembered() {
+        escape("yield", NameContext::Class);
+        let warned = WARNED_RENAMES.lock().unwrap();
+        assert!(warned.contains(&(NameContext::Class, "yield".to_string())));
+        assert!(!warned.contains(&(NameContext::Method, "yield".to_string())));
+    }
+}
@@ -3,29 +3,40 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
 use uniffi_bindgen::backend::{CodeType, Literal};
+use uniffi_meta::ObjectImpl;
+
+use super::PhpCodeType;
 
 #[derive(Debug)]
 pub struct ObjectCodeType {
     id: String,
+    imp: ObjectImpl,
 }
 
 impl ObjectCodeType {
-    pub fn new(id: String) -> Self {
-        Self { id }
+    pub fn new(id: String, imp: ObjectImpl) -> Self {
+        Self { id, imp }
     }
 }
 
 impl CodeType for ObjectCodeType {
     fn type_label(&self) -> String {
-        let name = super::GoCodeOracle.class_name(&self.id);
-        format!("*{}", name)
+        super::PHPCodeOracle.class_name(&self.id)
     }
 
     fn canonical_name(&self) -> String {
-        self.id.clone()
+        format!("Type{}", self.id)
     }
 
     fn literal(&self, _literal: &Literal) -> String {
-        unreachable!();
+        unreachable!("objects have no literals, see `unsupported::check_defaults`");
+    }
+
+    fn initialization_fn(&self) -> Option<String> {
+        self.imp
+            .has_callback_interface()
+            .then(|| format!("{}::register", self.ffi_converter_name()))
     }
 }
+
+impl PhpCodeType for ObjectCodeType {}
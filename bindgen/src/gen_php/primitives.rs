@@ -0,0 +1,103 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use paste::paste;
+use uniffi_bindgen::backend::{CodeType, Literal};
+use uniffi_meta::Radix;
+
+use super::PhpCodeType;
+
+pub(super) fn render_literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Boolean(v) => format!("{v}"),
+        Literal::String(s) => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+        // Octal literals have the `0` prefix rather than `0o`, which needs PHP 8.1.
+        //
+        // PHP reads a literal too large for an int as a float, so negative ones can't be in
+        // two's complement, and the smallest int can only be named.
+        Literal::Int(i64::MIN, ..) => "\\PHP_INT_MIN".into(),
+        Literal::Int(i, radix, _) => {
+            let sign = if *i < 0 { "-" } else { "" };
+            let i = i.unsigned_abs();
+            match radix {
+                Radix::Octal => format!("{sign}0{i:o}"),
+                Radix::Decimal => format!("{sign}{i}"),
+                Radix::Hexadecimal => format!("{sign}{i:#x}"),
+            }
+        }
+        Literal::UInt(i, radix, _) => match radix {
+            Radix::Octal => format!("0{i:o}"),
+            Radix::Decimal => format!("{i}"),
+            Radix::Hexadecimal => format!("{i:#x}"),
+        },
+        Literal::Float(string, _) => string.clone(),
+        _ => unreachable!("{literal:?} isn't a primitive, see `unsupported::check_defaults`"),
+    }
+}
+
+macro_rules! impl_code_type_for_primitive {
+    ($T:ty, $type_label:literal, $doc_type_label:literal, $canonical_name:literal) => {
+        paste! {
+            #[derive(Debug)]
+            pub struct $T;
+
+            impl CodeType for $T  {
+                fn type_label(&self) -> String {
+                    $type_label.into()
+                }
+
+                fn canonical_name(&self) -> String {
+                    $canonical_name.into()
+                }
+
+                fn literal(&self, literal: &Literal) -> String {
+                    render_literal(&literal)
+                }
+            }
+
+            impl PhpCodeType for $T {
+                fn doc_type_label(&self) -> String {
+                    $doc_type_label.into()
+                }
+            }
+        }
+    };
+}
+
+impl_code_type_for_primitive!(BooleanCodeType, "bool", "bool", "Boolean");
+impl_code_type_for_primitive!(StringCodeType, "string", "string", "String");
+impl_code_type_for_primitive!(BytesCodeType, "string", "string", "Bytes");
+impl_code_type_for_primitive!(Int8CodeType, "int", "int<-128, 127>", "Int8");
+impl_code_type_for_primitive!(Int16CodeType, "int", "int<-32768, 32767>", "Int16");
+impl_code_type_for_primitive!(Int32CodeType, "int", "int", "Int32");
+impl_code_type_for_primitive!(Int64CodeType, "int", "int", "Int64");
+impl_code_type_for_primitive!(UInt8CodeType, "int", "int<0, 255>", "UInt8");
+impl_code_type_for_primitive!(UInt16CodeType, "int", "int<0, 65535>", "UInt16");
+impl_code_type_for_primitive!(UInt32CodeType, "int", "non-negative-int", "UInt32");
+impl_code_type_for_primitive!(UInt64CodeType, "int", "non-negative-int", "UInt64");
+impl_code_type_for_primitive!(Float32CodeType, "float", "float", "Float32");
+impl_code_type_for_primitive!(Float64CodeType, "float", "float", "Float64");
+
+#[cfg(test)]
+mod tests {
+    use uniffi_meta::Type;
+
+    use super::*;
+
+    #[test]
+    fn int_literals() {
+        let int = |i, radix| render_literal(&Literal::Int(i, radix, Type::Int64));
+        assert_eq!(int(42, Radix::Decimal), "42");
+        assert_eq!(int(-42, Radix::Decimal), "-42");
+        assert_eq!(int(255, Radix::Hexadecimal), "0xff");
+        assert_eq!(int(-1, Radix::Hexadecimal), "-0x1");
+        assert_eq!(int(i64::MIN, Radix::Hexadecimal), "\\PHP_INT_MIN");
+        assert_eq!(int(i64::MIN, Radix::Decimal), "\\PHP_INT_MIN");
+        assert_eq!(int(8, Radix::Octal), "010");
+        assert_eq!(int(-8, Radix::Octal), "-010");
+        let uint = |i, radix| render_literal(&Literal::UInt(i, radix, Type::UInt64));
+        assert_eq!(uint(255, Radix::Hexadecimal), "0xff");
+        assert_eq!(uint(8, Radix::Octal), "010");
+    }
+}
@@ -0,0 +1,369 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! Split the single-file bindings into a PSR-4 layout, one file per class.
+//!
+//! This relies on the shape of what our templates generate: everything sits in bracketed
+//! `namespace Foo { ... }` blocks, and every top-level declaration starts at column 0 and
+//! ends with a `}` alone on a line.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{bail, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+
+/// The namespace shared by every UniFFI component.
+pub const RUNTIME_NAMESPACE: &str = "Uniffi";
+
+/// The sub-namespace (and directory) for the FFI plumbing users never touch directly.
+const FFI_NAMESPACE: &str = "Ffi";
+
+/// Generated files, with paths relative to the output directory.
+pub struct Psr4Bindings {
+    pub files: Vec<(Utf8PathBuf, String)>,
+    /// Files which declare functions, which can't be autoloaded and need including up front
+    /// (composer's `autoload.files`).
+    pub autoload_files: Vec<Utf8PathBuf>,
+}
+
+/// A top-level declaration, along with any doc comment before it.
+struct Decl {
+    name: String,
+    is_function: bool,
+    code: String,
+}
+
+#[derive(Default)]
+struct NamespaceBlock {
+    decls: Vec<Decl>,
+    /// Top-level statements, which run when the single file is loaded.
+    statements: Vec<String>,
+}
+
+pub fn split(library: &str, namespace: &str) -> Result<Psr4Bindings> {
+    let blocks = parse_namespace_blocks(library)?;
+    let mut files = Vec::new();
+    let mut autoload_files = Vec::new();
+
+    for (block_namespace, block) in blocks {
+        if block_namespace == RUNTIME_NAMESPACE {
+            let (functions, classes): (Vec<_>, Vec<_>) =
+                block.decls.into_iter().partition(|d| d.is_function);
+            for decl in classes {
+                files.push(class_file(RUNTIME_NAMESPACE, &decl, &[])?);
+            }
+            if !functions.is_empty() {
+                let path = namespace_dir(RUNTIME_NAMESPACE).join("functions.php");
+                files.push((path.clone(), php_file(RUNTIME_NAMESPACE, &[], &functions)));
+                autoload_files.push(path);
+            }
+            continue;
+        }
+        if block_namespace != namespace {
+            bail!("Unexpected namespace `{block_namespace}` in the generated bindings");
+        }
+
+        let ffi_namespace = ffi_namespace(namespace);
+        let (functions, classes): (Vec<_>, Vec<_>) =
+            block.decls.into_iter().partition(|d| d.is_function);
+        let (ffi_classes, public_classes): (Vec<_>, Vec<_>) =
+            classes.into_iter().partition(|d| is_ffi_class(&d.name));
+        let ffi_names: BTreeSet<_> = ffi_classes.iter().map(|d| d.name.clone()).collect();
+        let public_names: BTreeSet<_> = public_classes.iter().map(|d| d.name.clone()).collect();
+
+        for decl in &public_classes {
+            let uses = used_names(&decl.code, &ffi_names, &ffi_namespace);
+            files.push(class_file(namespace, decl, &uses)?);
+        }
+        for mut decl in ffi_classes {
+            // Statements used to run when the single file was included, now they run when the
+            // library class is first autoloaded.
+            if decl.name == "UniffiLib" && !block.statements.is_empty() {
+                decl.code.truncate(decl.code.trim_end().len());
+                decl.code.push_str("\n\n");
+                decl.code.push_str(&block.statements.join("\n"));
+            }
+            let uses = used_names(&decl.code, &public_names, namespace);
+            files.push(class_file(&ffi_namespace, &decl, &uses)?);
+        }
+        if !functions.is_empty() {
+            let code: String = functions.iter().map(|d| d.code.as_str()).collect();
+            let uses = used_names(&code, &ffi_names, &ffi_namespace);
+            let path = namespace_dir(namespace).join("functions.php");
+            files.push((path.clone(), php_file(namespace, &uses, &functions)));
+            autoload_files.push(path);
+        }
+    }
+
+    Ok(Psr4Bindings {
+        files,
+        autoload_files,
+    })
+}
+
+/// The directory, relative to the output directory, holding the classes of a namespace.
+pub fn namespace_dir(namespace: &str) -> Utf8PathBuf {
+    namespace
+        .split('\\')
+        .fold(Utf8Path::new("src").to_owned(), |dir, part| dir.join(part))
+}
+
+/// The namespace of the FFI plumbing of the component in `namespace`.
+pub fn ffi_namespace(namespace: &str) -> String {
+    format!("{namespace}\\{FFI_NAMESPACE}")
+}
+
+fn is_ffi_class(name: &str) -> bool {
+    // `Uniffi` itself is how users take control of loading the library.
+    (name.starts_with("Uniffi") && name != "Uniffi") || name.starts_with("FfiConverter")
+}
+
+fn class_file(namespace: &str, decl: &Decl, uses: &[String]) -> Result<(Utf8PathBuf, String)> {
+    let path = namespace_dir(namespace).join(format!("{}.php", decl.name));
+    Ok((path, php_file(namespace, uses, std::slice::from_ref(decl))))
+}
+
+fn php_file(namespace: &str, uses: &[String], decls: &[Decl]) -> String {
+    let mut out = String::from("<?php\n\n");
+    out.push_str(&format!("// {}\n\n", super::GENERATED_HEADER));
+    out.push_str("declare(strict_types=1);\n\n");
+    out.push_str(&format!("namespace {namespace};\n\n"));
+    for name in uses {
+        out.push_str(&format!("use {name};\n"));
+    }
+    if !uses.is_empty() {
+        out.push('\n');
+    }
+    let code: Vec<_> = decls.iter().map(|d| d.code.trim_end()).collect();
+    out.push_str(&code.join("\n\n"));
+    out.push('\n');
+    out
+}
+
+/// The fully qualified names of the `candidates` which `code` mentions.
+fn used_names(code: &str, candidates: &BTreeSet<String>, namespace: &str) -> Vec<String> {
+    let used: BTreeSet<_> = code
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '\\'))
+        .filter(|word| candidates.contains(*word))
+        .collect();
+    used.into_iter()
+        .map(|name| format!("{namespace}\\{name}"))
+        .collect()
+}
+
+fn parse_namespace_blocks(library: &str) -> Result<BTreeMap<String, NamespaceBlock>> {
+    let mut blocks: BTreeMap<String, NamespaceBlock> = BTreeMap::new();
+    let mut lines = library.lines();
+    while let Some(line) = lines.next() {
+        let Some(namespace) = line
+            .strip_prefix("namespace ")
+            .and_then(|rest| rest.strip_suffix(" {"))
+        else {
+            continue;
+        };
+        let block = blocks.entry(namespace.to_string()).or_default();
+        let mut pending = String::new();
+        loop {
+            let Some(line) = lines.next() else {
+                bail!("Unterminated namespace block `{namespace}`");
+            };
+            if line == "}" {
+                break;
+            }
+            if line.is_empty() || line.starts_with("// ") {
+                // Section comments only make sense in the single file.
+                pending.clear();
+                continue;
+            }
+            if line.starts_with("/**") || line.starts_with(" *") {
+                pending.push_str(line);
+                pending.push('\n');
+                continue;
+            }
+            match declared_name(line) {
+                Some((name, is_function)) => {
+                    let mut code = std::mem::take(&mut pending);
+                    code.push_str(line);
+                    code.push('\n');
+                    // A declaration on a single line has nothing more to read.
+                    if !line.trim_end().ends_with('}') || line.trim_end().ends_with('{') {
+                        for line in lines.by_ref() {
+                            code.push_str(line);
+                            code.push('\n');
+                            if line == "}" {
+                                break;
+                            }
+                        }
+                    }
+                    block.decls.push(Decl {
+                        name,
+                        is_function,
+                        code,
+                    });
+                }
+                None => {
+                    pending.clear();
+                    block.statements.push(line.to_string());
+                }
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+/// If `line` starts a declaration, the name it declares and whether it's a function.
+///
+/// Declarations guarded by `if (!\class_exists(...)) {` count as the thing they declare.
+fn declared_name(line: &str) -> Option<(String, bool)> {
+    if let Some(guard) = line.strip_prefix("if (!\\") {
+        let (check, rest) = guard.split_once('(')?;
+        let name = rest.split([',', ')']).next()?;
+        let name = name
+            .trim_end_matches("::class")
+            .trim_start_matches("__NAMESPACE__ . '\\")
+            .trim_end_matches('\'');
+        return Some((name.to_string(), check == "function_exists"));
+    }
+    let mut words = line
+        .split_whitespace()
+        .skip_while(|w| matches!(*w, "final" | "abstract" | "readonly"));
+    let keyword = words.next()?;
+    let name = words.next()?;
+    let name: String = name
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+        .collect();
+    match keyword {
+        "class" | "interface" | "enum" | "trait" => Some((name, false)),
+        "function" => Some((name, true)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uniffi_bindgen::ComponentInterface;
+
+    use super::super::Config;
+
+    const UDL: &str = r#"
+        namespace shop {
+            u32 total(sequence<Item> items);
+        };
+        dictionary Item {
+            string name;
+            u32 price;
+        };
+        [Error]
+        enum ShopError { "OutOfStock" };
+        interface Basket {
+            constructor();
+            [Throws=ShopError]
+            void add(Item item);
+        };
+    "#;
+
+    fn library() -> String {
+        let ci = ComponentInterface::from_webidl(UDL, "shop").unwrap();
+        let mut config: Config = toml::from_str(r#"layout = "psr4""#).unwrap();
+        config.module_name = Some("shop".into());
+        config.namespace = Some("Shop".into());
+        super::super::generate_bindings(&config, &ci)
+            .unwrap()
+            .library
+    }
+
+    fn file<'a>(bindings: &'a Psr4Bindings, path: &str) -> &'a str {
+        match bindings.files.iter().find(|(p, _)| p == path) {
+            Some((_, contents)) => contents,
+            None => panic!("no `{path}` in the bindings"),
+        }
+    }
+
+    /// The names declared at the top level of `code`, a file or the single-file library.
+    fn declared_names(code: &str) -> BTreeSet<String> {
+        let code = match code.split_once("\nnamespace ") {
+            // Files have a `namespace Foo;` statement rather than blocks.
+            Some((_, rest)) => match rest.split_once(";\n") {
+                Some((namespace, rest)) if !namespace.contains('\n') => {
+                    format!("namespace {namespace} {{\n{rest}}}\n")
+                }
+                _ => code.to_string(),
+            },
+            None => code.to_string(),
+        };
+        parse_namespace_blocks(&code)
+            .unwrap()
+            .into_values()
+            .flat_map(|block| block.decls.into_iter().map(|d| d.name))
+            .collect()
+    }
+
+    #[test]
+    fn every_class_gets_a_file_named_after_it() {
+        let library = library();
+        let bindings = split(&library, "Shop").unwrap();
+        let mut names = BTreeSet::new();
+        for (path, contents) in &bindings.files {
+            let declared = declared_names(contents);
+            if path.file_name() != Some("functions.php") {
+                assert_eq!(
+                    declared,
+                    BTreeSet::from([path.file_stem().unwrap().to_string()]),
+                    "{path}"
+                );
+            }
+            names.extend(declared);
+        }
+        // Nothing gets lost on the way.
+        assert_eq!(names, declared_names(&library));
+        for path in [
+            "src/Shop/Basket.php",
+            "src/Shop/BasketInterface.php",
+            "src/Shop/Item.php",
+            "src/Shop/ShopError.php",
+            "src/Shop/ShopErrorOutOfStock.php",
+            "src/Uniffi/NativeLibraries.php",
+        ] {
+            file(&bindings, path);
+        }
+    }
+
+    #[test]
+    fn ffi_plumbing_goes_in_its_own_namespace() {
+        let bindings = split(&library(), "Shop").unwrap();
+        let converter = file(&bindings, "src/Shop/Ffi/FfiConverterTypeItem.php");
+        assert!(converter.contains("\nnamespace Shop\\Ffi;\n\nuse Shop\\Item;\n"));
+        let lib = file(&bindings, "src/Shop/Ffi/UniffiLib.php");
+        assert!(lib.contains("\nnamespace Shop\\Ffi;\n"));
+        // Users load the library through `Uniffi`, so it stays with the public classes.
+        let uniffi = file(&bindings, "src/Shop/Uniffi.php");
+        assert!(uniffi.contains("\nnamespace Shop;\n"));
+        let basket = file(&bindings, "src/Shop/Basket.php");
+        assert!(basket.contains("\nuse Shop\\Ffi\\FfiConverterTypeItem;\n"));
+        assert!(basket.contains("\nuse Shop\\Ffi\\UniffiLib;\n"));
+    }
+
+    #[test]
+    fn functions_are_autoload_files() {
+        let bindings = split(&library(), "Shop").unwrap();
+        assert_eq!(
+            bindings.autoload_files,
+            ["src/Shop/functions.php", "src/Uniffi/functions.php"]
+        );
+        let functions = file(&bindings, "src/Shop/functions.php");
+        assert!(functions.contains("\nfunction total(array $items): int\n"));
+        assert!(functions.contains("\nuse Shop\\Ffi\\FfiConverterSequenceTypeItem;\n"));
+    }
+
+    #[test]
+    fn other_namespaces_are_an_error() {
+        let error = split(&library(), "Store").err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "Unexpected namespace `Shop` in the generated bindings"
+        );
+    }
+}
@@ -0,0 +1,34 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+use uniffi_bindgen::backend::{CodeType, Literal};
+
+use super::PhpCodeType;
+
+#[derive(Debug)]
+pub struct RecordCodeType {
+    id: String,
+}
+
+impl RecordCodeType {
+    pub fn new(id: String) -> Self {
+        Self { id }
+    }
+}
+
+impl CodeType for RecordCodeType {
+    fn type_label(&self) -> String {
+        super::PHPCodeOracle.class_name(&self.id)
+    }
+
+    fn canonical_name(&self) -> String {
+        format!("Type{}", self.id)
+    }
+
+    fn literal(&self, _literal: &Literal) -> String {
+        unreachable!("records have no literals, see `unsupported::check_defaults`");
+    }
+}
+
+impl PhpCodeType for RecordCodeType {}
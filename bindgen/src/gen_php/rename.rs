@@ -0,0 +1,349 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The `rename` and `exclude` options, which change what the bindings expose without touching
+//! the Rust crate.
+//!
+//! Items are named by their Rust names: `Type` or `function` at the top level, and
+//! `Type.member` for methods, constructors, record fields and enum variants.
+
+use std::collections::{BTreeSet, HashSet};
+
+use anyhow::{bail, Result};
+use uniffi_bindgen::interface::{AsType, Callable, ComponentInterface, Type};
+
+use super::Config;
+
+/// Check that every renamed and excluded item exists, and that nothing left in the bindings
+/// refers to an excluded type.
+pub fn validate(config: &Config, ci: &ComponentInterface) -> Result<()> {
+    let (renameable, excludable) = item_names(ci);
+    for name in config.rename.keys() {
+        if !renameable.contains(name) {
+            bail!(
+                "`rename` refers to `{name}`, which isn't in the `{}` component",
+                ci.namespace()
+            );
+        }
+    }
+    for name in &config.exclude {
+        if excludable.contains(name) {
+            continue;
+        }
+        if renameable.contains(name) {
+            bail!(
+                "`{name}` can't be excluded, only types, functions, methods and constructors can"
+            );
+        }
+        bail!(
+            "`exclude` refers to `{name}`, which isn't in the `{}` component",
+            ci.namespace()
+        );
+    }
+
+    let excluded: HashSet<&str> = config.exclude.iter().map(String::as_str).collect();
+    for obj in ci.object_definitions() {
+        // PHP implementations are called through a vtable with every method in it.
+        if !obj.has_callback_interface() || excluded.contains(obj.name()) {
+            continue;
+        }
+        for meth in obj.methods() {
+            let name = format!("{}.{}", obj.name(), meth.name());
+            if excluded.contains(name.as_str()) {
+                bail!(
+                    "`{name}` can't be excluded, `{}` can be implemented in PHP",
+                    obj.name()
+                );
+            }
+        }
+    }
+    for (user, type_) in type_uses(ci, &excluded) {
+        if let Some(name) = excluded_type(&type_, &excluded) {
+            bail!("`{user}` refers to `{name}`, which is excluded");
+        }
+    }
+    Ok(())
+}
+
+/// Every type the items left in the bindings refer to, along with the name of the item.
+pub fn type_uses(ci: &ComponentInterface, excluded: &HashSet<&str>) -> Vec<(String, Type)> {
+    let mut uses = Vec::new();
+    let mut add_callable = |user: String, callable: &dyn Callable| {
+        let types = callable
+            .arguments()
+            .into_iter()
+            .map(|arg| arg.as_type())
+            .chain(callable.return_type())
+            .chain(callable.throws_type());
+        for type_ in types {
+            uses.push((user.clone(), type_));
+        }
+    };
+
+    for func in ci.function_definitions() {
+        if !excluded.contains(func.name()) {
+            add_callable(func.name().to_string(), func);
+        }
+    }
+    for obj in ci.object_definitions() {
+        if excluded.contains(obj.name()) {
+            continue;
+        }
+        for cons in obj.constructors() {
+            let name = format!("{}.{}", obj.name(), cons.name());
+            if !excluded.contains(name.as_str()) {
+                add_callable(name, cons);
+            }
+        }
+        for meth in obj.methods() {
+            let name = format!("{}.{}", obj.name(), meth.name());
+            if !excluded.contains(name.as_str()) {
+                add_callable(name, meth);
+            }
+        }
+    }
+    for cbi in ci.callback_interface_definitions() {
+        if !excluded.contains(cbi.name()) {
+            for meth in cbi.methods() {
+                add_callable(format!("{}.{}", cbi.name(), meth.name()), meth);
+            }
+        }
+    }
+    for rec in ci.record_definitions() {
+        if !excluded.contains(rec.name()) {
+            for field in rec.fields() {
+                uses.push((format!("{}.{}", rec.name(), field.name()), field.as_type()));
+            }
+        }
+    }
+    for e in ci.enum_definitions() {
+        if !excluded.contains(e.name()) {
+            for variant in e.variants() {
+                for field in variant.fields() {
+                    uses.push((format!("{}.{}", e.name(), variant.name()), field.as_type()));
+                }
+            }
+        }
+    }
+    uses
+}
+
+/// The names of the items which can be renamed, and of those which can be excluded.
+fn item_names(ci: &ComponentInterface) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut renameable = BTreeSet::new();
+    let mut excludable = BTreeSet::new();
+    for func in ci.function_definitions() {
+        excludable.insert(func.name().to_string());
+    }
+    for obj in ci.object_definitions() {
+        excludable.insert(obj.name().to_string());
+        for cons in obj.constructors() {
+            excludable.insert(format!("{}.{}", obj.name(), cons.name()));
+        }
+        for meth in obj.methods() {
+            excludable.insert(format!("{}.{}", obj.name(), meth.name()));
+        }
+    }
+    for cbi in ci.callback_interface_definitions() {
+        excludable.insert(cbi.name().to_string());
+        for meth in cbi.methods() {
+            renameable.insert(format!("{}.{}", cbi.name(), meth.name()));
+        }
+    }
+    for rec in ci.record_definitions() {
+        excludable.insert(rec.name().to_string());
+        for field in rec.fields() {
+            renameable.insert(format!("{}.{}", rec.name(), field.name()));
+        }
+    }
+    for e in ci.enum_definitions() {
+        excludable.insert(e.name().to_string());
+        for variant in e.variants() {
+            renameable.insert(format!("{}.{}", e.name(), variant.name()));
+        }
+    }
+    renameable.extend(excludable.iter().cloned());
+    (renameable, excludable)
+}
+
+/// The excluded type which `type_` is, or contains.
+pub fn excluded_type(type_: &Type, excluded: &HashSet<&str>) -> Option<String> {
+    match type_ {
+        Type::Optional { inner_type } | Type::Sequence { inner_type } => {
+            excluded_type(inner_type, excluded)
+        }
+        Type::Map {
+            key_type,
+            value_type,
+        } => excluded_type(key_type, excluded).or_else(|| excluded_type(value_type, excluded)),
+        _ => definition_name(type_)
+            .filter(|name| excluded.contains(name))
+            .map(str::to_string),
+    }
+}
+
+/// The name of the type `type_` if it's one the component defines. Unlike `Type::name()` this
+/// includes callback interfaces, and doesn't look inside optionals and sequences.
+pub fn definition_name(type_: &Type) -> Option<&str> {
+    match type_ {
+        Type::Object { name, .. }
+        | Type::Record { name, .. }
+        | Type::Enum { name, .. }
+        | Type::CallbackInterface { name, .. }
+        | Type::Custom { name, .. }
+        | Type::External { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{OracleContext, PHPCodeOracle};
+    use super::*;
+
+    const UDL: &str = r#"
+        namespace shop {
+            Basket new_basket();
+            void log(string message);
+        };
+        dictionary Item {
+            string name;
+            u32 price;
+        };
+        enum Size { "Small", "Large" };
+        interface Basket {
+            constructor();
+            void add(Item item);
+            Size largest();
+            sequence<Item>? items();
+        };
+        callback interface Listener {
+            void on_change(Item item);
+        };
+        [Trait, WithForeign]
+        interface Pricing {
+            u32 price(Item item);
+        };
+    "#;
+
+    fn ci() -> ComponentInterface {
+        ComponentInterface::from_webidl(UDL, "shop").unwrap()
+    }
+
+    fn config(toml: &str) -> Config {
+        toml::from_str(toml).unwrap()
+    }
+
+    fn validate_error(toml: &str) -> String {
+        validate(&config(toml), &ci()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn renames_and_exclusions_of_existing_items() {
+        let config = config(
+            r#"
+            exclude = ["log", "Basket.largest", "Size", "Listener"]
+            [rename]
+            Item = "Product"
+            "Item.price" = "cost"
+            "Basket.add" = "put"
+            "Size.Small" = "Tiny"
+            "Listener.on_change" = "changed"
+            "#,
+        );
+        validate(&config, &ci()).unwrap();
+    }
+
+    #[test]
+    fn renames_apply_to_the_generated_names() {
+        let config = config(
+            r#"
+            [rename]
+            Item = "Product"
+            "Item.price" = "cost"
+            "Basket.add" = "put"
+            "Size.Small" = "Tiny"
+            "#,
+        );
+        let ci = ci();
+        let _context = OracleContext::enter(&config, &ci);
+        assert_eq!(PHPCodeOracle.class_name("Item"), "Product");
+        assert_eq!(PHPCodeOracle.class_name("Basket"), "Basket");
+        assert_eq!(PHPCodeOracle.field_name("price", "Item"), "cost");
+        assert_eq!(PHPCodeOracle.field_name("name", "Item"), "name");
+        assert_eq!(PHPCodeOracle.member_method_name("add", "Basket"), "put");
+        assert_eq!(PHPCodeOracle.enum_variant_name("Small", "Size"), "Tiny");
+        assert_eq!(PHPCodeOracle.enum_variant_name("Large", "Size"), "large");
+    }
+
+    #[test]
+    fn renaming_a_missing_item() {
+        assert_eq!(
+            validate_error(r#"rename = { "Basket.remove" = "take" }"#),
+            "`rename` refers to `Basket.remove`, which isn't in the `shop` component"
+        );
+    }
+
+    #[test]
+    fn excluding_a_missing_item() {
+        assert_eq!(
+            validate_error(r#"exclude = ["Cart"]"#),
+            "`exclude` refers to `Cart`, which isn't in the `shop` component"
+        );
+    }
+
+    #[test]
+    fn excluding_a_field_or_variant() {
+        assert_eq!(
+            validate_error(r#"exclude = ["Item.price"]"#),
+            "`Item.price` can't be excluded, only types, functions, methods and constructors can"
+        );
+        assert_eq!(
+            validate_error(r#"exclude = ["Size.Large"]"#),
+            "`Size.Large` can't be excluded, only types, functions, methods and constructors can"
+        );
+    }
+
+    #[test]
+    fn excluding_a_method_php_implements() {
+        assert_eq!(
+            validate_error(r#"exclude = ["Pricing.price"]"#),
+            "`Pricing.price` can't be excluded, `Pricing` can be implemented in PHP"
+        );
+        validate(&config(r#"exclude = ["Pricing"]"#), &ci()).unwrap();
+    }
+
+    #[test]
+    fn excluding_a_type_still_in_use() {
+        assert_eq!(
+            validate_error(r#"exclude = ["Size"]"#),
+            "`Basket.largest` refers to `Size`, which is excluded"
+        );
+        // Inside an optional sequence too.
+        assert_eq!(
+            validate_error(r#"exclude = ["Item", "Basket.add", "Listener", "Pricing"]"#),
+            "`Basket.items` refers to `Item`, which is excluded"
+        );
+    }
+
+    #[test]
+    fn excluded_types_inside_others() {
+        let excluded = HashSet::from(["Item"]);
+        let item = Type::Record {
+            module_path: "shop".into(),
+            name: "Item".into(),
+        };
+        let map = Type::Map {
+            key_type: Box::new(Type::String),
+            value_type: Box::new(Type::Sequence {
+                inner_type: Box::new(item.clone()),
+            }),
+        };
+        assert_eq!(excluded_type(&item, &excluded).as_deref(), Some("Item"));
+        assert_eq!(excluded_type(&map, &excluded).as_deref(), Some("Item"));
+        assert_eq!(excluded_type(&Type::String, &excluded), None);
+        assert_eq!(definition_name(&map), None);
+        assert_eq!(definition_name(&item), Some("Item"));
+    }
+}
@@ -0,0 +1,621 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! The parts of a component the bindings can't handle yet. We look for them before rendering
+//! anything, so errors name the UDL item rather than pointing somewhere inside a template.
+//!
+//! By default the first one fails generation. With `allow_unsupported`, types which are (or
+//! contain) something unsupported are left out, and the functions, methods and constructors
+//! using them become stubs throwing `UnsupportedException`. Everything left out is listed in a
+//! report.
+//!
+//! Async functions are only supported with `same_thread_wakers`, see the `Config`.
+//!
+//! Rendering relies on what's checked here: `PHPCodeOracle::find` is never given a timestamp,
+//! a duration or an external type from another library, and the `literal` of a code type is only
+//! given defaults of its type.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use uniffi_bindgen::{
+    backend::Literal,
+    interface::{AsType, Callable, ComponentInterface, Type},
+};
+
+use super::{rename, Config};
+
+/// Everything the bindings leave out of a component.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    pub component: String,
+    pub unsupported: Vec<Unsupported>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Unsupported {
+    /// Named as in `rename` and `exclude`: `Type`, `function` or `Type.member`.
+    pub item: String,
+    pub kind: ItemKind,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Type,
+    Function,
+    Constructor,
+    Method,
+}
+
+impl ItemKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ItemKind::Type => "type",
+            ItemKind::Function => "function",
+            ItemKind::Constructor => "constructor",
+            ItemKind::Method => "method",
+        }
+    }
+}
+
+impl Report {
+    /// Find the unsupported items of `ci`, leaving out the excluded ones.
+    pub fn find(config: &Config, ci: &ComponentInterface) -> Report {
+        let excluded: HashSet<&str> = config.exclude.iter().map(String::as_str).collect();
+        let mut report = Report {
+            component: ci.namespace().to_string(),
+            unsupported: Vec::new(),
+        };
+
+        // A type is unsupported if anything in it is, which can be another unsupported type,
+        // so keep going until we stop finding new ones.
+        let definitions = type_definitions(ci, &excluded);
+        let mut types = HashSet::new();
+        for (name, crate_name, ..) in ci.iter_external_types() {
+            let generated = config
+                .external_types
+                .contains_key(&(crate_name.clone(), name.clone()));
+            if !generated && !excluded.contains(name.as_str()) {
+                types.insert(name.clone());
+                report.unsupported.push(Unsupported {
+                    item: name.clone(),
+                    kind: ItemKind::Type,
+                    reason: format!(
+                        "is an external type from `{crate_name}`, which needs its bindings generated from the same library"
+                    ),
+                });
+            }
+        }
+        loop {
+            let found = report.unsupported.len();
+            for (name, members) in &definitions {
+                if types.contains(name) {
+                    continue;
+                }
+                let reason = members.iter().find_map(|(member, type_)| {
+                    let what = unsupported_type(type_, &|name| types.contains(name))?;
+                    Some(format!("`{member}` uses {what}"))
+                });
+                if let Some(reason) = reason {
+                    types.insert(name.clone());
+                    report.unsupported.push(Unsupported {
+                        item: name.clone(),
+                        kind: ItemKind::Type,
+                        reason,
+                    });
+                }
+            }
+            if report.unsupported.len() == found {
+                break;
+            }
+        }
+
+        let mut add_callable = |item: String, kind: ItemKind, callable: &dyn Callable| {
+            let reason = if callable.is_async() && !config.same_thread_wakers {
+                Some("is async, which needs `same_thread_wakers` in the config".to_string())
+            } else {
+                callable_types(callable)
+                    .iter()
+                    .find_map(|type_| unsupported_type(type_, &|name| types.contains(name)))
+                    .map(|what| format!("uses {what}"))
+            };
+            if let Some(reason) = reason {
+                report.unsupported.push(Unsupported { item, kind, reason });
+            }
+        };
+        for func in ci.function_definitions() {
+            if !excluded.contains(func.name()) {
+                add_callable(func.name().to_string(), ItemKind::Function, func);
+            }
+        }
+        for obj in ci.object_definitions() {
+            if excluded.contains(obj.name()) || types.contains(obj.name()) {
+                continue;
+            }
+            for cons in obj.constructors() {
+                let name = format!("{}.{}", obj.name(), cons.name());
+                if !excluded.contains(name.as_str()) {
+                    add_callable(name, ItemKind::Constructor, cons);
+                }
+            }
+            for meth in obj.methods() {
+                let name = format!("{}.{}", obj.name(), meth.name());
+                if !excluded.contains(name.as_str()) {
+                    add_callable(name, ItemKind::Method, meth);
+                }
+            }
+        }
+        report
+    }
+
+    /// Fail on the first unsupported item, unless the config allows them.
+    pub fn check(&self, config: &Config) -> Result<()> {
+        match self.unsupported.first() {
+            Some(Unsupported { item, reason, .. }) if !config.allow_unsupported => bail!(
+                "`{item}` isn't supported by the PHP bindings ({reason}), pass `--allow-unsupported` to generate everything else"
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unsupported.is_empty()
+    }
+
+    /// A table of the unsupported items, one per line.
+    pub fn table(&self) -> String {
+        let item_width = self
+            .unsupported
+            .iter()
+            .map(|u| u.item.len())
+            .max()
+            .unwrap_or(0);
+        self.unsupported
+            .iter()
+            .map(|u| {
+                format!(
+                    "    {:11}  {:item_width$}  {}\n",
+                    u.kind.as_str(),
+                    u.item,
+                    u.reason
+                )
+            })
+            .collect()
+    }
+}
+
+/// Fail on a default which isn't a value of its type, naming the argument or field.
+pub fn check_defaults(ci: &ComponentInterface) -> Result<()> {
+    let mut defaults = Vec::new();
+    for rec in ci.record_definitions() {
+        for field in rec.fields() {
+            let item = format!("`{}.{}`", rec.name(), field.name());
+            defaults.push((item, field.as_type(), field.default_value().cloned()));
+        }
+    }
+    let mut add_callable = |item: String, callable: &dyn Callable| {
+        for arg in callable.arguments() {
+            let item = format!("`{item}` argument `{}`", arg.name());
+            defaults.push((item, arg.as_type(), arg.default_value().cloned()));
+        }
+    };
+    for func in ci.function_definitions() {
+        add_callable(func.name().to_string(), func);
+    }
+    for obj in ci.object_definitions() {
+        for cons in obj.constructors() {
+            add_callable(format!("{}.{}", obj.name(), cons.name()), cons);
+        }
+        for meth in obj.methods() {
+            add_callable(format!("{}.{}", obj.name(), meth.name()), meth);
+        }
+    }
+    for cbi in ci.callback_interface_definitions() {
+        for meth in cbi.methods() {
+            add_callable(format!("{}.{}", cbi.name(), meth.name()), meth);
+        }
+    }
+    for (item, type_, default) in defaults {
+        match default {
+            Some(literal) if !is_literal_of(ci, &literal, &type_) => {
+                bail!("{item} has a default of the wrong type for PHP: {literal:?}")
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Whether `literal` is a value of `type_`. Numbers can be any number, as long as PHP can
+/// convert it.
+fn is_literal_of(ci: &ComponentInterface, literal: &Literal, type_: &Type) -> bool {
+    match (literal, type_) {
+        (Literal::Boolean(_), Type::Boolean) | (Literal::String(_), Type::String) => true,
+        (
+            Literal::Int(..) | Literal::UInt(..),
+            Type::Int8
+            | Type::Int16
+            | Type::Int32
+            | Type::Int64
+            | Type::UInt8
+            | Type::UInt16
+            | Type::UInt32
+            | Type::UInt64,
+        ) => true,
+        (
+            Literal::Int(..) | Literal::UInt(..) | Literal::Float(..),
+            Type::Float32 | Type::Float64,
+        ) => true,
+        (Literal::None, Type::Optional { .. }) => true,
+        (Literal::Some { inner }, Type::Optional { inner_type }) => {
+            is_literal_of(ci, inner, inner_type)
+        }
+        (Literal::EmptySequence, Type::Sequence { .. }) => true,
+        (Literal::EmptyMap, Type::Map { .. }) => true,
+        (Literal::Enum(variant, _), Type::Enum { name, .. }) => ci
+            .get_enum_definition(name)
+            .is_some_and(|e| e.variants().iter().any(|v| v.name() == variant)),
+        (_, Type::Custom { builtin, .. }) => is_literal_of(ci, literal, builtin),
+        // We don't know what the type is, only which PHP it's generated as.
+        (
+            Literal::Boolean(_)
+            | Literal::String(_)
+            | Literal::Int(..)
+            | Literal::UInt(..)
+            | Literal::Float(..)
+            | Literal::Enum(..),
+            Type::External { .. },
+        ) => true,
+        _ => false,
+    }
+}
+
+/// The types which can only be generated if everything in them is supported, with the types
+/// of their members. Objects are only here if they can be implemented in PHP, otherwise their
+/// unsupported methods and constructors are left out on their own.
+fn type_definitions(
+    ci: &ComponentInterface,
+    excluded: &HashSet<&str>,
+) -> Vec<(String, Vec<(String, Type)>)> {
+    let mut definitions = Vec::new();
+    for rec in ci.record_definitions() {
+        let members = rec
+            .fields()
+            .iter()
+            .map(|field| (field.name().to_string(), field.as_type()))
+            .collect();
+        definitions.push((rec.name().to_string(), members));
+    }
+    for e in ci.enum_definitions() {
+        let members = e
+            .variants()
+            .iter()
+            .flat_map(|variant| {
+                variant
+                    .fields()
+                    .iter()
+                    .map(|field| (variant.name().to_string(), field.as_type()))
+            })
+            .collect();
+        definitions.push((e.name().to_string(), members));
+    }
+    for cbi in ci.callback_interface_definitions() {
+        let members = cbi
+            .methods()
+            .into_iter()
+            .flat_map(|meth| {
+                callable_types(meth)
+                    .into_iter()
+                    .map(|type_| (meth.name().to_string(), type_))
+            })
+            .collect();
+        definitions.push((cbi.name().to_string(), members));
+    }
+    for obj in ci.object_definitions() {
+        if obj.has_callback_interface() {
+            let members = obj
+                .methods()
+                .into_iter()
+                .flat_map(|meth| {
+                    callable_types(meth)
+                        .into_iter()
+                        .map(|type_| (meth.name().to_string(), type_))
+                })
+                .collect();
+            definitions.push((obj.name().to_string(), members));
+        }
+    }
+    definitions.retain(|(name, _)| !excluded.contains(name.as_str()));
+    definitions
+}
+
+/// The types of a callable's arguments, return value and error.
+fn callable_types(callable: &dyn Callable) -> Vec<Type> {
+    callable
+        .arguments()
+        .into_iter()
+        .map(|arg| arg.as_type())
+        .chain(callable.return_type())
+        .chain(callable.throws_type())
+        .collect()
+}
+
+/// Whether values of `type_` survive being a PHP array key: PHP turns booleans and floats into
+/// ints, and anything else can't be a key at all.
+fn is_array_key(type_: &Type) -> bool {
+    match type_ {
+        Type::String
+        | Type::Bytes
+        | Type::Int8
+        | Type::Int16
+        | Type::Int32
+        | Type::Int64
+        | Type::UInt8
+        | Type::UInt16
+        | Type::UInt32
+        | Type::UInt64 => true,
+        Type::Custom { builtin, .. } => is_array_key(builtin),
+        _ => false,
+    }
+}
+
+/// What's unsupported about `type_`, if anything: "a timestamp", for instance.
+/// `is_unsupported` says whether a named type is.
+pub fn unsupported_type(type_: &Type, is_unsupported: &dyn Fn(&str) -> bool) -> Option<String> {
+    match type_ {
+        Type::Timestamp => Some("a timestamp".into()),
+        Type::Duration => Some("a duration".into()),
+        Type::Optional { inner_type } | Type::Sequence { inner_type } => {
+            unsupported_type(inner_type, is_unsupported)
+        }
+        Type::Map { key_type, .. } if !is_array_key(key_type) => {
+            Some("a map whose keys can't be PHP array keys".into())
+        }
+        Type::Map {
+            key_type,
+            value_type,
+        } => unsupported_type(key_type, is_unsupported)
+            .or_else(|| unsupported_type(value_type, is_unsupported)),
+        Type::Custom { builtin, .. } => unsupported_type(builtin, is_unsupported),
+        _ => match rename::definition_name(type_) {
+            Some(name) if is_unsupported(name) => Some(format!("`{name}`, which isn't supported")),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UDL: &str = r#"
+        namespace clock {
+            timestamp now();
+            u32 ticks();
+        };
+        dictionary Event {
+            string name;
+            timestamp at;
+        };
+        dictionary Log {
+            sequence<Event> events;
+        };
+        interface Clock {
+            constructor();
+            duration uptime();
+            u32 count();
+        };
+    "#;
+
+    fn ci() -> ComponentInterface {
+        ComponentInterface::from_webidl(UDL, "clock").unwrap()
+    }
+
+    fn config(toml: &str) -> Config {
+        let mut config: Config = toml::from_str(toml).unwrap();
+        config.module_name = Some("clock".into());
+        config.namespace = Some("Clock".into());
+        config
+    }
+
+    #[test]
+    fn unsupported_items_fail_generation() {
+        let ci = ci();
+        let config = config("");
+        let error = Report::find(&config, &ci)
+            .check(&config)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "`Event` isn't supported by the PHP bindings (`at` uses a timestamp), pass `--allow-unsupported` to generate everything else"
+        );
+        // Rather than a panic somewhere in the templates.
+        let Err(error) = super::super::generate_bindings(&config, &ci) else {
+            panic!("generated bindings using a timestamp");
+        };
+        assert!(error.to_string().starts_with("`Event` isn't supported"));
+    }
+
+    #[test]
+    fn excluded_items_dont_fail_generation() {
+        let ci = ci();
+        let config = config(r#"exclude = ["Event", "Log", "now", "Clock.uptime"]"#);
+        let report = Report::find(&config, &ci);
+        assert!(report.is_empty());
+        report.check(&config).unwrap();
+        super::super::generate_bindings(&config, &ci).unwrap();
+    }
+
+    fn items(report: &Report) -> Vec<(&str, ItemKind, &str)> {
+        report
+            .unsupported
+            .iter()
+            .map(|u| (u.item.as_str(), u.kind, u.reason.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn report_lists_what_is_left_out() {
+        let report = Report::find(&config(""), &ci());
+        assert_eq!(report.component, "clock");
+        assert_eq!(
+            items(&report),
+            [
+                ("Event", ItemKind::Type, "`at` uses a timestamp"),
+                (
+                    "Log",
+                    ItemKind::Type,
+                    "`events` uses `Event`, which isn't supported"
+                ),
+                ("now", ItemKind::Function, "uses a timestamp"),
+                ("Clock.uptime", ItemKind::Method, "uses a duration"),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_as_json_and_table() {
+        let report = Report::find(&config(r#"exclude = ["Event", "Log"]"#), &ci());
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "component": "clock",
+                "unsupported": [
+                    { "item": "now", "kind": "function", "reason": "uses a timestamp" },
+                    { "item": "Clock.uptime", "kind": "method", "reason": "uses a duration" },
+                ],
+            })
+        );
+        assert_eq!(
+            report.table(),
+            "    function     now           uses a timestamp\n    method       Clock.uptime  uses a duration\n"
+        );
+    }
+
+    #[test]
+    fn allow_unsupported_generates_everything_else() {
+        let ci = ci();
+        let bindings =
+            super::super::generate_bindings(&config("allow_unsupported = true"), &ci).unwrap();
+        assert_eq!(bindings.unsupported.unsupported.len(), 4);
+        let library = bindings.library;
+        assert!(library.contains("function ticks("));
+        assert!(library.contains("function now(...$args): never\n"));
+        assert!(library.contains("public function uptime(...$args): never\n"));
+        assert!(library.contains("`now` isn't supported by the PHP bindings (uses a timestamp)"));
+        assert!(library
+            .contains("`Clock.uptime` isn't supported by the PHP bindings (uses a duration)"));
+        assert!(!library.contains("class Event"));
+        assert!(!library.contains("class Log"));
+
+        // `never` is PHP 8.1's.
+        let php74 = config("allow_unsupported = true\nphp_version = \"7.4\"");
+        let library = super::super::generate_bindings(&php74, &ci)
+            .unwrap()
+            .library;
+        assert!(library.contains("function now(...$args)\n"));
+    }
+
+    #[test]
+    fn async_needs_same_thread_wakers() {
+        let ci =
+            ComponentInterface::from_webidl("namespace waiter { [Async] u32 wait(); };", "waiter")
+                .unwrap();
+        assert_eq!(
+            items(&Report::find(&config(""), &ci)),
+            [(
+                "wait",
+                ItemKind::Function,
+                "is async, which needs `same_thread_wakers` in the config"
+            )]
+        );
+        assert!(Report::find(&config("same_thread_wakers = true"), &ci).is_empty());
+    }
+
+    #[test]
+    fn external_types_from_another_library() {
+        let udl = r#"
+            namespace diary {
+                void write(Entry entry);
+            };
+            [External="calendar"]
+            typedef extern Day;
+            dictionary Entry {
+                Day day;
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(udl, "diary").unwrap();
+        assert_eq!(
+            items(&Report::find(&config(""), &ci)),
+            [
+                (
+                    "Day",
+                    ItemKind::Type,
+                    "is an external type from `calendar`, which needs its bindings generated from the same library"
+                ),
+                ("Entry", ItemKind::Type, "`day` uses `Day`, which isn't supported"),
+                ("write", ItemKind::Function, "uses `Entry`, which isn't supported"),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_need_keys_php_arrays_keep() {
+        let udl = r#"
+            namespace maps {
+                record<string, u32> by_name(record<string, u32> counts);
+                record<i64, string> by_id();
+                record<boolean, string> by_flag();
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(udl, "maps").unwrap();
+        let config = config("allow_unsupported = true");
+        assert_eq!(
+            items(&Report::find(&config, &ci)),
+            [(
+                "by_flag",
+                ItemKind::Function,
+                "uses a map whose keys can't be PHP array keys"
+            )]
+        );
+        // PHP makes numeric strings like "1" int keys, which are strings again on the way back.
+        let library = super::super::generate_bindings(&config, &ci)
+            .unwrap()
+            .library;
+        assert!(library.contains("FfiConverterString::write((string) $key, $writer);"));
+        assert!(library.contains("FfiConverterInt64::write($key, $writer);"));
+    }
+
+    #[test]
+    fn defaults_must_be_values_of_their_type() {
+        let udl = r#"
+            namespace modes {
+                void run(optional Mode mode = "Fast");
+            };
+            enum Mode { "Fast", "Slow" };
+            dictionary Settings {
+                Mode mode = "Medium";
+            };
+        "#;
+        let ci = ComponentInterface::from_webidl(udl, "modes").unwrap();
+        let error = check_defaults(&ci).unwrap_err().to_string();
+        assert!(
+            error.starts_with(
+                r#"`Settings.mode` has a default of the wrong type for PHP: Enum("Medium""#
+            ),
+            "{error}"
+        );
+        // Rather than a panic somewhere in the templates.
+        let Err(error) = super::super::generate_bindings(&config(""), &ci) else {
+            panic!("generated bindings with a default that isn't a variant");
+        };
+        assert!(error
+            .to_string()
+            .starts_with("`Settings.mode` has a default"));
+    }
+}
@@ -2,17 +2,97 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
+mod cargo;
 pub mod gen_php;
 
+use std::ffi::OsString;
+
+use anyhow::{bail, Context};
 use camino::Utf8PathBuf;
-use clap::Parser;
-use gen_php::BindingGeneratorPHP;
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand};
+use gen_php::{BindingGeneratorPHP, Output, PhpVersion};
 
 #[derive(Parser)]
 #[clap(name = "uniffi-bindgen")]
 #[clap(version = clap::crate_version!())]
 #[clap(propagate_version = true)]
 struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate the PHP bindings. This is what runs when no subcommand is given.
+    #[clap(group(library_needs_out_dir()))]
+    Generate {
+        #[clap(flatten)]
+        output: OutputArgs,
+
+        /// Same as the `stubs` subcommand.
+        #[clap(long, hide = true)]
+        stubs: bool,
+
+        #[clap(flatten)]
+        source: SourceArgs,
+    },
+
+    /// Check the bindings in the output directory are up to date, without writing anything.
+    /// Exits with an error if any file is missing or different.
+    #[clap(group(library_needs_out_dir()))]
+    Check {
+        #[clap(flatten)]
+        output: OutputArgs,
+
+        #[clap(flatten)]
+        source: SourceArgs,
+    },
+
+    /// Write IDE stubs (`<module>.stub.php`) with the signatures and documentation of the
+    /// bindings, instead of the bindings themselves.
+    #[clap(group(library_needs_out_dir()))]
+    Stubs {
+        #[clap(flatten)]
+        output: OutputArgs,
+
+        #[clap(flatten)]
+        source: SourceArgs,
+    },
+
+    /// Write bindings which call into Rust through a native PHP extension instead of `ext-ffi`,
+    /// along with the C source of the extension (in `ext/`). Sets `backend` in the config.
+    ///
+    /// The extension can't call back into PHP, so components with callback interfaces, trait
+    /// interfaces PHP can implement or async functions need the `ffi` backend of `generate`.
+    #[clap(group(library_needs_out_dir()))]
+    Extension {
+        #[clap(flatten)]
+        output: OutputArgs,
+
+        #[clap(flatten)]
+        source: SourceArgs,
+    },
+
+    /// Write a C header (`<module>.h`) declaring the FFI, for `FFI::load()` and preloading.
+    #[clap(group(library_needs_out_dir()))]
+    Header {
+        /// Directory in which to write the header. Default is same folder as .udl file.
+        #[clap(long, short)]
+        out_dir: Option<Utf8PathBuf>,
+
+        #[clap(flatten)]
+        source: SourceArgs,
+    },
+
+    /// Print the FFI functions of the component, with their C and PHP types.
+    PrintFfi {
+        #[clap(flatten)]
+        source: SourceArgs,
+    },
+}
+
+#[derive(Args)]
+struct OutputArgs {
     /// Directory in which to write generated files. Default is same folder as .udl file.
     #[clap(long, short)]
     out_dir: Option<Utf8PathBuf>,
@@ -21,6 +101,16 @@ struct Cli {
     #[clap(long, short)]
     no_format: bool,
 
+    /// Leave out what the bindings don't support yet instead of failing: functions and methods
+    /// become stubs throwing `UnsupportedException`, and `<module>.unsupported.json` lists what's
+    /// missing. Sets `allow_unsupported` in the config.
+    #[clap(long)]
+    allow_unsupported: bool,
+}
+
+#[derive(Args)]
+#[clap(group(ArgGroup::new("cargo").args(&["manifest-path", "package"]).multiple(true)))]
+struct SourceArgs {
     /// Path to optional uniffi config file. This config will be merged on top of default
     /// `uniffi.toml` config in crate root. The merge recursively upserts TOML keys into
     /// the default config.
@@ -28,43 +118,152 @@ struct Cli {
     config: Option<Utf8PathBuf>,
 
     /// Extract proc-macro metadata from a native lib (cdylib or staticlib) for this crate.
-    #[clap(long, short)]
+    #[clap(long, short, conflicts_with_all = &["library-mode", "cargo"])]
     lib_file: Option<Utf8PathBuf>,
 
     /// Pass in a cdylib path rather than a UDL file
-    #[clap(long = "library")]
+    #[clap(long = "library", conflicts_with = "cargo")]
     library_mode: bool,
 
-    /// When `--library` is passed, only generate bindings for one crate.
+    /// Find the cdylib of the package with `cargo metadata` instead of passing it in, and
+    /// generate bindings for each crate with UniFFI components in it, each into its own
+    /// `--out-dir/<crate>`. Each crate's `uniffi.toml` is found the same way as `--library`.
+    #[clap(long, conflicts_with = "source")]
+    manifest_path: Option<Utf8PathBuf>,
+
+    /// Like `--manifest-path`, for this package of the workspace rather than the root one.
+    #[clap(long, short, conflicts_with = "source")]
+    package: Option<String>,
+
+    /// With `--manifest-path` or `--package`, use the library built with `--release`.
+    #[clap(long, requires = "cargo")]
+    release: bool,
+
+    /// When `--library`, `--manifest-path` or `--package` is passed, only generate bindings for
+    /// one crate.
     /// When `--library` is not passed, use this as the crate name instead of attempting to
     /// locate and parse Cargo.toml.
     #[clap(long = "crate")]
     crate_name: Option<String>,
 
+    /// The oldest PHP version the generated code has to run on: 7.4, 8.1 or 8.3.
+    /// Overrides `php_version` in the config.
+    #[clap(long)]
+    php_version: Option<PhpVersion>,
+
     /// Path to the UDL file, or cdylib if `library-mode` is specified
-    source: Utf8PathBuf,
+    #[clap(required_unless_present = "cargo")]
+    source: Option<Utf8PathBuf>,
+}
+
+/// `--library`, `--manifest-path` and `--package` write wherever `--out-dir` says, there's no
+/// UDL file to write next to.
+fn library_needs_out_dir() -> ArgGroup<'static> {
+    ArgGroup::new("library")
+        .args(&["library-mode", "manifest-path", "package"])
+        .multiple(true)
+        .requires("out-dir")
 }
 
 pub fn main() -> anyhow::Result<()> {
-    let Cli {
-        out_dir,
-        no_format,
+    let Cli { command } = Cli::parse_from(with_default_subcommand(std::env::args_os()));
+
+    match command {
+        Command::Generate {
+            output,
+            stubs,
+            source,
+        } => {
+            let mode = if stubs {
+                Output::Stubs
+            } else {
+                Output::Bindings
+            };
+            run(mode, source, output)
+        }
+        Command::Check { output, source } => run(Output::Check, source, output),
+        Command::Stubs { output, source } => run(Output::Stubs, source, output),
+        Command::Extension { output, source } => run(Output::Extension, source, output),
+        Command::Header { out_dir, source } => {
+            let output = OutputArgs {
+                out_dir,
+                no_format: true,
+                allow_unsupported: false,
+            };
+            run(Output::Header, source, output)
+        }
+        Command::PrintFfi { source } => {
+            // Nothing gets written, but library mode wants an output directory all the same.
+            let output = OutputArgs {
+                out_dir: Some(".".into()),
+                no_format: true,
+                allow_unsupported: false,
+            };
+            run(Output::FfiListing, source, output)
+        }
+    }
+}
+
+/// Our scripts predate the subcommands, so without one we run `generate`.
+fn with_default_subcommand(args: impl Iterator<Item = OsString>) -> Vec<OsString> {
+    let mut args: Vec<OsString> = args.collect();
+    let cli = Cli::command();
+    let needs_subcommand = match args.get(1).and_then(|arg| arg.to_str()) {
+        Some("help" | "-h" | "--help" | "-V" | "--version") => false,
+        Some(arg) => cli.find_subcommand(arg).is_none(),
+        None => args.len() > 1,
+    };
+    if needs_subcommand {
+        args.insert(1, "generate".into());
+    }
+    args
+}
+
+fn run(output: Output, source: SourceArgs, output_args: OutputArgs) -> anyhow::Result<()> {
+    let SourceArgs {
         config,
         lib_file,
         library_mode,
+        manifest_path,
+        package,
+        release,
         crate_name,
+        php_version,
         source,
-    } = Cli::parse();
+    } = source;
+    let OutputArgs {
+        out_dir,
+        no_format,
+        allow_unsupported,
+    } = output_args;
+    let try_format_code = !no_format;
 
     let binding_gen = BindingGeneratorPHP {
-        try_format_code: !no_format,
+        try_format_code,
+        php_version,
+        output,
+        allow_unsupported,
     };
-    if library_mode {
-        if lib_file.is_some() {
-            panic!("--lib-file is not compatible with --library.")
+    if manifest_path.is_some() || package.is_some() {
+        let out_dir =
+            out_dir.context("--out-dir is required when using --manifest-path or --package")?;
+        let library = cargo::locate(manifest_path.as_deref(), package.as_deref(), release)?;
+        if let Some(crate_name) = &crate_name {
+            if !library.crate_names.contains(crate_name) {
+                bail!("Crate {crate_name} not found in {}", library.library_path);
+            }
         }
-        let out_dir = out_dir.expect("--out-dir is required when using --library");
-        let library_path = source;
+        cargo::generate_bindings(
+            &library,
+            crate_name.as_deref(),
+            &binding_gen,
+            config.as_deref(),
+            &out_dir,
+            try_format_code,
+        )?;
+    } else if library_mode {
+        let out_dir = out_dir.context("--out-dir is required when using --library")?;
+        let library_path = source.context("the cdylib is required when using --library")?;
 
         uniffi_bindgen::library_mode::generate_bindings(
             &library_path,
@@ -72,10 +271,10 @@ pub fn main() -> anyhow::Result<()> {
             &binding_gen,
             config.as_deref(),
             &out_dir,
-            !no_format,
+            try_format_code,
         )?;
     } else {
-        let udl_file = source;
+        let udl_file = source.context("the UDL file is required")?;
         uniffi_bindgen::generate_bindings(
             &udl_file,
             config.as_deref(),
@@ -83,7 +282,7 @@ pub fn main() -> anyhow::Result<()> {
             out_dir.as_deref(),
             lib_file.as_deref(),
             crate_name.as_deref(),
-            !no_format,
+            try_format_code,
         )?;
     }
 
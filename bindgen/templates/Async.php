@@ -0,0 +1,240 @@
+/**
+ * Drives a Rust future to completion.
+ *
+ * Rust calls back into PHP when a future can make progress, from the thread that wakes it.
+ * PHP can't be entered from any other thread than its own, so the library has to wake its
+ * futures from the thread polling them, which `same_thread_wakers` in the config promises.
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+ *
+{%- if features.fibers %}
+ * Outside of a `\Fiber` the calling thread blocks until the future is ready. Inside a
+ * `\Fiber` we suspend between polls instead, so whatever scheduler resumes the fiber can
+ * get on with other work in the meantime. If the fiber is destroyed, or an exception is
+ * thrown into it, while we wait, the Rust future is cancelled.
+{%- else %}
+ * The calling thread blocks until the future is ready.
+{%- endif %}
+{%- when AsyncRuntime::Amp %}
+ *
+ * Each call returns an `\Amp\Future`. The fiber driving it suspends until Rust's continuation
+ * callback fires, which schedules its resumption on the Revolt event loop. Requesting the
+ * `\Amp\Cancellation` passed to the call cancels the Rust future.
+{%- when AsyncRuntime::React %}
+ *
+ * Each call returns a ReactPHP promise. Rust's continuation callback schedules the next poll
+ * (or the completion) on the ReactPHP event loop. Cancelling the promise cancels the Rust
+ * future.
+{%- endmatch %}
+ */
+final class UniffiAsync
+{
+    // These match the values from the uniffi::rustcalls module
+    public const POLL_READY = 0;
+    public const POLL_MAYBE_READY = 1;
+
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+
+    /** @var array<int, int> poll results reported by the continuation callback, keyed by future handle */
+    private static array $pollResults = [];
+{%- else %}
+
+    /** @var array<int, \Closure(int): void> what to do once a future's continuation fires, keyed by future handle */
+    private static array $wakers = [];
+{%- endmatch %}
+
+    private static ?\Closure $continuationCallback = null;
+
+    /**
+     * The `RustFutureContinuationCallback` we hand to every `rust_future_poll` call.
+     *
+     * Rust may invoke this while we poll, or later from whatever wakes the future, so it does
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+     * nothing except record the result for the polling loop to pick up.
+{%- else %}
+     * nothing except hand the result to the waker, which defers any real work to the event loop.
+{%- endmatch %}
+     */
+    private static function continuationCallback(): \Closure
+    {
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+        return self::$continuationCallback ??= static function (int $handle, int $pollResult): void {
+            self::$pollResults[$handle] = $pollResult;
+        };
+{%- else %}
+        return self::$continuationCallback ??= static function (int $handle, int $pollResult): void {
+            $waker = self::$wakers[$handle];
+            unset(self::$wakers[$handle]);
+            $waker($pollResult);
+        };
+{%- endmatch %}
+    }
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+
+    public static function call(
+        int $rustFuture,
+        string $pollFn,
+        string $cancelFn,
+        string $completeFn,
+        string $freeFn,
+        ?\Closure $liftFn,
+        ?string $errorConverter
+    ){% if features.mixed_type %}: mixed{% endif %} {
+        $ready = false;
+        try {
+            self::waitUntilReady($rustFuture, $pollFn);
+            $ready = true;
+            return self::complete($rustFuture, $completeFn, $liftFn, $errorConverter);
+        } finally {
+            if (!$ready) {
+                // We were interrupted while waiting, don't leave the Rust task running.
+                unset(self::$pollResults[$rustFuture]);
+                UniffiLib::ffi()->$cancelFn($rustFuture);
+            }
+            UniffiLib::ffi()->$freeFn($rustFuture);
+        }
+    }
+
+    private static function waitUntilReady(int $rustFuture, string $pollFn): void
+    {
+        $delay = 10;
+        while (true) {
+            unset(self::$pollResults[$rustFuture]);
+            UniffiLib::ffi()->$pollFn($rustFuture, self::continuationCallback(), $rustFuture);
+            // The waker may not have fired yet; keep yielding until it reports back.
+            while (!\array_key_exists($rustFuture, self::$pollResults)) {
+                $delay = self::pause($delay);
+            }
+            if (self::$pollResults[$rustFuture] === self::POLL_READY) {
+                unset(self::$pollResults[$rustFuture]);
+                return;
+            }
+        }
+    }
+
+    /**
+{%- if features.fibers %}
+     * Give up control for a moment: suspend the current fiber if there is one, otherwise sleep
+     * with a capped exponential backoff. Returns the delay to use next time.
+{%- else %}
+     * Sleep for a moment, with a capped exponential backoff. Returns the delay to use next time.
+{%- endif %}
+     */
+    private static function pause(int $delay): int
+    {
+        {%- if features.fibers %}
+        {%- if self.has_async_callback_methods() %}
+        UniffiForeignFutures::tick();
+        {%- endif %}
+        if (\Fiber::getCurrent() !== null) {
+            \Fiber::suspend();
+            return $delay;
+        }
+        {%- endif %}
+        \usleep($delay);
+        return \min($delay * 2, 10_000);
+    }
+{%- when AsyncRuntime::Amp %}
+
+    public static function call(
+        int $rustFuture,
+        string $pollFn,
+        string $cancelFn,
+        string $completeFn,
+        string $freeFn,
+        ?\Closure $liftFn,
+        ?string $errorConverter,
+        ?\Amp\Cancellation $cancellation
+    ): \Amp\Future {
+        return \Amp\async(static function () use ($rustFuture, $pollFn, $cancelFn, $completeFn, $freeFn, $liftFn, $errorConverter, $cancellation): mixed {
+            // Cancelling the Rust future wakes it, and completing it then throws a `CancelledException`.
+            // The subscriber may be queued after we're done, so it checks we haven't freed the future yet.
+            $done = false;
+            $subscription = $cancellation?->subscribe(static function () use (&$done, $rustFuture, $cancelFn): void {
+                if (!$done) {
+                    UniffiLib::ffi()->$cancelFn($rustFuture);
+                }
+            });
+            try {
+                do {
+                    $suspension = \Revolt\EventLoop::getSuspension();
+                    self::$wakers[$rustFuture] = static function (int $pollResult) use ($suspension): void {
+                        \Revolt\EventLoop::queue($suspension->resume(...), $pollResult);
+                    };
+                    UniffiLib::ffi()->$pollFn($rustFuture, self::continuationCallback(), $rustFuture);
+                } while ($suspension->suspend() !== self::POLL_READY);
+                return self::complete($rustFuture, $completeFn, $liftFn, $errorConverter);
+            } finally {
+                $done = true;
+                if ($subscription !== null) {
+                    $cancellation->unsubscribe($subscription);
+                }
+                unset(self::$wakers[$rustFuture]);
+                UniffiLib::ffi()->$freeFn($rustFuture);
+            }
+        });
+    }
+{%- when AsyncRuntime::React %}
+
+    public static function call(
+        int $rustFuture,
+        string $pollFn,
+        string $cancelFn,
+        string $completeFn,
+        string $freeFn,
+        ?\Closure $liftFn,
+        ?string $errorConverter
+    ): \React\Promise\PromiseInterface {
+        $done = false;
+        // Cancelling the Rust future wakes it, and completing it then rejects the promise with a
+        // `CancelledException`.
+        $deferred = new \React\Promise\Deferred(static function () use (&$done, $rustFuture, $cancelFn): void {
+            if (!$done) {
+                UniffiLib::ffi()->$cancelFn($rustFuture);
+            }
+        });
+        self::poll($rustFuture, $pollFn, static function () use (&$done, $deferred, $rustFuture, $completeFn, $freeFn, $liftFn, $errorConverter): void {
+            try {
+                $deferred->resolve(self::complete($rustFuture, $completeFn, $liftFn, $errorConverter));
+            } catch (\Throwable $e) {
+                $deferred->reject($e);
+            } finally {
+                $done = true;
+                UniffiLib::ffi()->$freeFn($rustFuture);
+            }
+        });
+        return $deferred->promise();
+    }
+
+    /**
+     * Poll the future, and keep re-polling it from the event loop until it's ready to complete.
+     */
+    private static function poll(int $rustFuture, string $pollFn, \Closure $onReady): void
+    {
+        self::$wakers[$rustFuture] = static function (int $pollResult) use ($rustFuture, $pollFn, $onReady): void {
+            \React\EventLoop\Loop::futureTick(
+                $pollResult === self::POLL_READY
+                    ? $onReady
+                    : static fn () => self::poll($rustFuture, $pollFn, $onReady)
+            );
+        };
+        UniffiLib::ffi()->$pollFn($rustFuture, self::continuationCallback(), $rustFuture);
+    }
+{%- endmatch %}
+
+    private static function complete(int $rustFuture, string $completeFn, ?\Closure $liftFn, ?string $errorConverter){% if features.mixed_type %}: mixed{% endif %}
+    {
+        $result = UniffiRustCall::callWithError(
+            $errorConverter,
+            fn (\FFI\CData $uniffiStatus) => UniffiLib::ffi()->$completeFn($rustFuture, $uniffiStatus)
+        );
+        return $liftFn === null ? null : $liftFn($result);
+    }
+}
+{%- if self.has_async_callback_methods() %}
+{% include "ForeignFutureTemplate.php" %}
+{%- endif %}
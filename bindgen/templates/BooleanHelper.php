@@ -0,0 +1,23 @@
+
+final class {{ ffi_converter_name }}
+{
+    public static function lift(int $value): bool
+    {
+        return $value !== 0;
+    }
+
+    public static function lower(bool $value): int
+    {
+        return $value ? 1 : 0;
+    }
+
+    public static function read(UniffiByteReader $reader): bool
+    {
+        return self::lift($reader->readInt8());
+    }
+
+    public static function write(bool $value, UniffiByteWriter $writer): void
+    {
+        $writer->writeInt8(self::lower($value));
+    }
+}
@@ -0,0 +1,95 @@
+
+    /**
+     * The vtable Rust calls through for PHP implementations. It must outlive every call,
+     * so we keep it in a static.
+     */
+    private static \FFI\CData $vtable;
+
+    public static function register(): void
+    {
+        self::$handleMap = new UniffiHandleMap();
+        $vtable = UniffiLib::ffi()->new('{{ vtable_type_name }}', false);
+        {%- for (ffi_callback, meth) in vtable_methods.iter() %}
+        {%- if !meth.is_async() %}
+        $vtable->{{ meth.name() }} = function (
+            int $uniffiHandle,
+            {%- for arg in meth.arguments() %}
+            {% if features.mixed_type %}mixed {% endif %}${{ arg.name()|var_name }},
+            {%- endfor %}
+            ?\FFI\CData $uniffiOutReturn,
+            \FFI\CData $uniffiCallStatus
+        ): void {
+            UniffiRustCall::traitInterfaceCall(
+                $uniffiCallStatus,
+                fn () => self::$handleMap->get($uniffiHandle)->{{ meth.name()|member_method_name(name) }}(
+                    {%- for arg in meth.arguments() %}
+                    {{ arg|lift_fn }}(${{ arg.name()|var_name }}),
+                    {%- endfor %}
+                ),
+                {%- match meth.return_type() %}
+                {%- when Some(return_type) %}
+                function ({% if features.mixed_type %}mixed {% endif %}$value) use ($uniffiOutReturn): void {
+                    $uniffiOutReturn[0] = {{ return_type|lower_fn }}($value);
+                },
+                {%- when None %}
+                function ({% if features.mixed_type %}mixed {% endif %}$value): void {
+                },
+                {%- endmatch %}
+                {%- match meth.throws_type() %}
+                {%- when Some(error_type) %}
+                {{ error_type|type_name }}::class,
+                {{ error_type|ffi_error_converter_name }}::class,
+                {%- when None %}
+                {%- endmatch %}
+            );
+        };
+        {%- else %}
+        {%- let result_struct = meth.foreign_future_ffi_result_struct() %}
+        $vtable->{{ meth.name() }} = function (
+            int $uniffiHandle,
+            {%- for arg in meth.arguments() %}
+            {% if features.mixed_type %}mixed {% endif %}${{ arg.name()|var_name }},
+            {%- endfor %}
+            \FFI\CData $uniffiFutureCallback,
+            int $uniffiCallbackData,
+            \FFI\CData $uniffiOutReturn
+        ): void {
+            UniffiForeignFutures::start(
+                {%- if config.async_runtime == AsyncRuntime::Amp %}
+                fn (\Amp\Cancellation $cancellation) => self::$handleMap->get($uniffiHandle)->{{ meth.name()|member_method_name(name) }}(
+                {%- else %}
+                fn () => self::$handleMap->get($uniffiHandle)->{{ meth.name()|member_method_name(name) }}(
+                {%- endif %}
+                    {%- for arg in meth.arguments() %}
+                    {{ arg|lift_fn }}(${{ arg.name()|var_name }}),
+                    {%- endfor %}
+                    {%- if config.async_runtime == AsyncRuntime::Amp %}
+                    $cancellation,
+                    {%- endif %}
+                ),
+                '{{ result_struct.name()|ffi_struct_name }}',
+                {%- match meth.return_type() %}
+                {%- when Some(return_type) %}
+                {% call php::converter_closure(return_type, "lower") %},
+                {%- when None %}
+                null,
+                {%- endmatch %}
+                $uniffiFutureCallback,
+                $uniffiCallbackData,
+                $uniffiOutReturn,
+                {%- match meth.throws_type() %}
+                {%- when Some(error_type) %}
+                {{ error_type|type_name }}::class,
+                {{ error_type|ffi_error_converter_name }}::class,
+                {%- when None %}
+                {%- endmatch %}
+            );
+        };
+        {%- endif %}
+        {%- endfor %}
+        $vtable->uniffi_free = function (int $uniffiHandle): void {
+            self::$handleMap->remove($uniffiHandle);
+        };
+        self::$vtable = $vtable;
+        UniffiLib::ffi()->{{ ffi_init_callback.name() }}(\FFI::addr(self::$vtable));
+    }
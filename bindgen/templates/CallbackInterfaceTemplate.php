@@ -0,0 +1,42 @@
+{%- let cbi = ci.get_callback_interface_definition(name).unwrap() %}
+{%- if self.include_once_check("HandleMap.php") %}{% include "HandleMap.php" %}{% endif %}
+{% call php::docstring(cbi, 0) %}
+interface {{ type_name }}
+{
+    {%- for meth in cbi.methods() %}
+    {%- if !loop.first %}
+{% endif %}
+    {%- call php::fn_docstring(meth, true, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}({% call php::arg_list_decl(meth) %}): {% call php::return_type_decl(meth) %};
+    {%- endfor %}
+}
+
+final class {{ ffi_converter_name }}
+{
+    private static UniffiHandleMap $handleMap;
+
+    public static function lift(int $handle): {{ type_name }}
+    {
+        return self::$handleMap->get($handle);
+    }
+
+    public static function lower({{ type_name }} $value): int
+    {
+        return self::$handleMap->insert($value);
+    }
+
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        return self::lift($reader->readUInt64());
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        $writer->writeUInt64(self::lower($value));
+    }
+    {%- let vtable = cbi.vtable() %}
+    {%- let vtable_type_name = vtable|ffi_type_name %}
+    {%- let vtable_methods = cbi.vtable_methods() %}
+    {%- let ffi_init_callback = cbi.ffi_init_callback() %}
+    {%- include "CallbackInterfaceImpl.php" %}
+}
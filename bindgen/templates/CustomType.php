@@ -0,0 +1,24 @@
+{#- Custom types are exposed as their builtin type, so we just forward to its FfiConverter. #}
+
+final class {{ ffi_converter_name }}
+{
+    public static function lift({% if features.mixed_type %}mixed {% endif %}$value): {{ type_name }}
+    {
+        return {{ builtin|lift_fn }}($value);
+    }
+
+    public static function lower({{ type_name }} $value){% if features.mixed_type %}: mixed{% endif %}
+    {
+        return {{ builtin|lower_fn }}($value);
+    }
+
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        return {{ builtin|read_fn }}($reader);
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        {{ builtin|write_fn }}($value, $writer);
+    }
+}
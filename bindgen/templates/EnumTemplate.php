@@ -0,0 +1,138 @@
+{%- if e.is_flat() %}
+{%- if features.native_enums %}
+{% call php::docstring(e, 0) %}
+enum {{ type_name }}: int
+{
+    {%- for variant in e.variants() %}
+    {%- call php::docstring(variant, 4) %}
+    case {{ variant.name()|enum_variant_php_quoted(name) }} = {{ e|variant_discr_literal(loop.index0) }};
+    {%- endfor %}
+}
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        return match ($reader->readInt32()) {
+            {%- for variant in e.variants() %}
+            {{ loop.index }} => {{ type_name }}::{{ variant.name()|enum_variant_php_quoted(name) }},
+            {%- endfor %}
+            default => throw new InternalException("Raw enum value doesn't match any cases"),
+        };
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        $writer->writeInt32(match ($value) {
+            {%- for variant in e.variants() %}
+            {{ type_name }}::{{ variant.name()|enum_variant_php_quoted(name) }} => {{ loop.index }},
+            {%- endfor %}
+        });
+    }
+}
+{%- else %}
+{%- let class_name = name|class_name %}
+{% call php::docstring(e, 0) %}
+final class {{ class_name }}
+{
+    {%- for variant in e.variants() %}
+    {%- call php::docstring(variant, 4) %}
+    public const {{ variant.name()|enum_variant_php_quoted(name) }} = {{ e|variant_discr_literal(loop.index0) }};
+    {%- endfor %}
+
+    private function __construct()
+    {
+    }
+}
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): int
+    {
+        switch ($reader->readInt32()) {
+            {%- for variant in e.variants() %}
+            case {{ loop.index }}:
+                return {{ class_name }}::{{ variant.name()|enum_variant_php_quoted(name) }};
+            {%- endfor %}
+            default:
+                throw new InternalException("Raw enum value doesn't match any cases");
+        }
+    }
+
+    public static function write(int $value, UniffiByteWriter $writer): void
+    {
+        switch ($value) {
+            {%- for variant in e.variants() %}
+            case {{ class_name }}::{{ variant.name()|enum_variant_php_quoted(name) }}:
+                $writer->writeInt32({{ loop.index }});
+                return;
+            {%- endfor %}
+            default:
+                throw new InternalException(\sprintf('%d is not a {{ class_name }} value', $value));
+        }
+    }
+}
+{%- endif %}
+{%- else %}
+{% call php::docstring(e, 0) %}
+abstract {% if features.readonly_classes %}readonly {% endif %}class {{ type_name }}
+{
+}
+{%- for variant in e.variants() %}
+{% call php::docstring(variant, 0) %}
+final {% if features.readonly_classes %}readonly {% endif %}class {{ type_name }}{{ variant.name()|variant_class_name(name) }} extends {{ type_name }}
+{
+    {%- if variant.has_fields() %}
+    {%- call php::variant_fields(variant.fields(), features.readonly_classes, false) %}
+    }
+    {%- call php::variant_getters(variant.fields(), false) %}
+    {%- endif %}
+}
+{%- endfor %}
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        {%- if features.php8_syntax %}
+        return match ($reader->readInt32()) {
+            {%- for variant in e.variants() %}
+            {{ loop.index }} => new {{ type_name }}{{ variant.name()|variant_class_name(name) }}(
+                {%- for field in variant.fields() %}
+                {{ field|read_fn }}($reader),
+                {%- endfor %}
+            ),
+            {%- endfor %}
+            default => throw new InternalException("Raw enum value doesn't match any cases"),
+        };
+        {%- else %}
+        switch ($reader->readInt32()) {
+            {%- for variant in e.variants() %}
+            case {{ loop.index }}:
+                return new {{ type_name }}{{ variant.name()|variant_class_name(name) }}(
+                    {%- for field in variant.fields() %}
+                    {{ field|read_fn }}($reader){% if !loop.last %},{% endif %}
+                    {%- endfor %}
+                );
+            {%- endfor %}
+            default:
+                throw new InternalException("Raw enum value doesn't match any cases");
+        }
+        {%- endif %}
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        {%- for variant in e.variants() %}
+        {% if !loop.first %}} else{% endif %}if ($value instanceof {{ type_name }}{{ variant.name()|variant_class_name(name) }}) {
+            $writer->writeInt32({{ loop.index }});
+            {%- for field in variant.fields() %}
+            {{ field|write_fn }}($value->{% call php::variant_field_value(field, loop.index0, false) %}, $writer);
+            {%- endfor %}
+        {%- endfor %}
+        {%- if !e.variants().is_empty() %}
+        }
+        {%- endif %}
+    }
+}
+{%- endif %}
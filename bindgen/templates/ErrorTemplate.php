@@ -0,0 +1,76 @@
+{% call php::docstring(e, 0) %}
+class {{ type_name }} extends \Exception
+{
+}
+{%- for variant in e.variants() %}
+{% call php::docstring(variant, 0) %}
+final class {{ type_name }}{{ variant.name()|variant_class_name(name) }} extends {{ type_name }}
+{
+    {%- if !e.is_flat() && variant.has_fields() %}
+    {%- call php::variant_fields(variant.fields(), false, true) %}
+        parent::__construct(\implode(', ', [
+            {%- for field in variant.fields() %}
+            '{% call php::field_name(field, loop.index0, true) %}=' . \var_export(${% call php::field_name(field, loop.index0, true) %}, true),
+            {%- endfor %}
+        ]));
+    }
+    {%- call php::variant_getters(variant.fields(), true) %}
+    {%- endif %}
+}
+{%- endfor %}
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        {%- if features.php8_syntax %}
+        return match ($reader->readInt32()) {
+            {%- for variant in e.variants() %}
+            {{ loop.index }} => new {{ type_name }}{{ variant.name()|variant_class_name(name) }}(
+                {%- if e.is_flat() %}
+                FfiConverterString::read($reader),
+                {%- else %}
+                {%- for field in variant.fields() %}
+                {{ field|read_fn }}($reader),
+                {%- endfor %}
+                {%- endif %}
+            ),
+            {%- endfor %}
+            default => throw new InternalException("Raw enum value doesn't match any cases"),
+        };
+        {%- else %}
+        switch ($reader->readInt32()) {
+            {%- for variant in e.variants() %}
+            case {{ loop.index }}:
+                return new {{ type_name }}{{ variant.name()|variant_class_name(name) }}(
+                    {%- if e.is_flat() %}
+                    FfiConverterString::read($reader)
+                    {%- else %}
+                    {%- for field in variant.fields() %}
+                    {{ field|read_fn }}($reader){% if !loop.last %},{% endif %}
+                    {%- endfor %}
+                    {%- endif %}
+                );
+            {%- endfor %}
+            default:
+                throw new InternalException("Raw enum value doesn't match any cases");
+        }
+        {%- endif %}
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        {%- for variant in e.variants() %}
+        {% if !loop.first %}} else{% endif %}if ($value instanceof {{ type_name }}{{ variant.name()|variant_class_name(name) }}) {
+            $writer->writeInt32({{ loop.index }});
+            {%- if !e.is_flat() %}
+            {%- for field in variant.fields() %}
+            {{ field|write_fn }}($value->{% call php::variant_field_value(field, loop.index0, true) %}, $writer);
+            {%- endfor %}
+            {%- endif %}
+        {%- endfor %}
+        {%- if !e.variants().is_empty() %}
+        }
+        {%- endif %}
+    }
+}
@@ -0,0 +1,42 @@
+typedef struct RustBuffer {
+    uint64_t capacity;
+    uint64_t len;
+    uint8_t *data;
+} RustBuffer;
+
+typedef struct ForeignBytes {
+    int32_t len;
+    const uint8_t *data;
+} ForeignBytes;
+
+typedef struct RustCallStatus {
+    int8_t code;
+    RustBuffer errorBuf;
+} RustCallStatus;
+{%- for def in definitions %}
+{%- match def %}
+{%- when FfiDefinition::CallbackFunction(callback) %}
+typedef {% match callback.return_type() %}{% when Some(return_type) %}{{ return_type|ffi_type_name }}{% when None %}void{% endmatch %} (*{{ callback.name()|ffi_callback_name }})(
+    {%- for arg in callback.arguments() -%}
+    {%- let arg_type = arg.type_() -%}
+    {{ arg_type|ffi_type_name }}{% if !loop.last || callback.has_rust_call_status_arg() %}, {% endif %}
+    {%- endfor -%}
+    {%- if callback.has_rust_call_status_arg() %}RustCallStatus*{% endif -%}
+);
+{%- when FfiDefinition::Struct(ffi_struct) %}
+typedef struct {{ ffi_struct.name()|ffi_struct_name }} {
+    {%- for field in ffi_struct.fields() %}
+    {%- let field_type = field.type_() %}
+    {{ field_type|ffi_type_name }} {{ field.name() }};
+    {%- endfor %}
+} {{ ffi_struct.name()|ffi_struct_name }};
+{%- when FfiDefinition::Function(func) %}
+{% match func.return_type() %}{% when Some(return_type) %}{{ return_type|ffi_type_name }}{% when None %}void{% endmatch %} {{ func.name() }}(
+    {%- for arg in func.arguments() -%}
+    {%- let arg_type = arg.type_() -%}
+    {{ arg_type|ffi_type_name }}{% if !loop.last || func.has_rust_call_status_arg() %}, {% endif %}
+    {%- endfor -%}
+    {%- if func.has_rust_call_status_arg() %}RustCallStatus*{% else %}{% if func.arguments().is_empty() %}void{% endif %}{% endif -%}
+);
+{%- endmatch %}
+{%- endfor %}
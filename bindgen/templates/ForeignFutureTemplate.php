@@ -0,0 +1,247 @@
+
+/**
+ * Runs the async methods of PHP callback interface implementations on behalf of Rust.
+ *
+ * Rust gets a `ForeignFuture` whose handle refers to the call in progress here. When the call
+ * settles we pass the result to the completion callback Rust gave us. Rust frees the
+ * `ForeignFuture` once it's done with it, or early if it no longer wants the result, in which
+ * case we cancel the call.
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+ *
+{%- if features.fibers %}
+ * Each call runs in its own `\Fiber`. Calls which suspend are resumed whenever
+ * `UniffiAsync` pauses while waiting on a Rust future.
+{%- else %}
+ * Without fibers, each call runs to completion before Rust gets its `ForeignFuture` back.
+{%- endif %}
+{%- when AsyncRuntime::Amp %}
+ *
+ * Implementations return an `\Amp\Future`, and are passed an `\Amp\Cancellation` which is
+ * requested if Rust cancels the call.
+{%- when AsyncRuntime::React %}
+ *
+ * Implementations return a ReactPHP promise, which is cancelled if Rust cancels the call.
+{%- endmatch %}
+ */
+final class UniffiForeignFutures
+{
+    private static ?UniffiHandleMap $calls = null;
+
+    private static ?\Closure $free = null;
+
+    /**
+     * @param \Closure $makeCall calls the PHP implementation
+     * @param string $resultStruct the `ForeignFutureStruct*` type the completion callback takes
+     * @param \Closure|null $lowerReturn lowers the return value, null if the method returns nothing
+     */
+    public static function start(
+        \Closure $makeCall,
+        string $resultStruct,
+        ?\Closure $lowerReturn,
+        \FFI\CData $futureCallback,
+        int $callbackData,
+        \FFI\CData $outReturn,
+        ?string $errorClass = null,
+        ?string $errorConverter = null
+    ): void {
+        self::$calls ??= new UniffiHandleMap();
+        $complete = static function (\Closure $getValue) use ($resultStruct, $lowerReturn, $futureCallback, $callbackData, $errorClass, $errorConverter): void {
+            $result = UniffiLib::ffi()->new($resultStruct);
+            UniffiRustCall::traitInterfaceCall(
+                \FFI::addr($result->call_status),
+                $getValue,
+                static function ({% if features.mixed_type %}mixed {% endif %}$value) use ($result, $lowerReturn): void {
+                    if ($lowerReturn !== null) {
+                        $result->return_value = $lowerReturn($value);
+                    }
+                },
+                $errorClass,
+                $errorConverter,
+            );
+            $futureCallback($callbackData, $result);
+        };
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+{%- if features.fibers %}
+
+        $fiber = new \Fiber($makeCall);
+        $call = new UniffiForeignCall($complete, $fiber);
+        $outReturn[0]->handle = self::$calls->insert($call);
+        $outReturn[0]->free = self::free();
+        $call->step(static fn () => $fiber->start());
+{%- else %}
+
+        $call = new UniffiForeignCall($complete);
+        $outReturn[0]->handle = self::$calls->insert($call);
+        $outReturn[0]->free = self::free();
+        $call->settle($makeCall);
+{%- endif %}
+{%- when AsyncRuntime::Amp %}
+
+        $cancellation = new \Amp\DeferredCancellation();
+        $call = new UniffiForeignCall($complete, $cancellation);
+        $outReturn[0]->handle = self::$calls->insert($call);
+        $outReturn[0]->free = self::free();
+        try {
+            $future = $makeCall($cancellation->getCancellation());
+        } catch (\Throwable $e) {
+            $call->settle(static fn () => throw $e);
+            return;
+        }
+        $future->map(static fn (mixed $value) => $call->settle(static fn () => $value))
+            ->catch(static fn (\Throwable $e) => $call->settle(static fn () => throw $e))
+            ->ignore();
+{%- when AsyncRuntime::React %}
+
+        try {
+            $promise = $makeCall();
+        } catch (\Throwable $e) {
+            $promise = \React\Promise\reject($e);
+        }
+        $call = new UniffiForeignCall($complete, $promise);
+        $outReturn[0]->handle = self::$calls->insert($call);
+        $outReturn[0]->free = self::free();
+        $promise->then(
+            static fn ({% if features.mixed_type %}mixed {% endif %}$value) => $call->settle(static fn () => $value),
+            static fn (\Throwable $e) => $call->settle(static function () use ($e): void {
+                throw $e;
+            })
+        );
+{%- endmatch %}
+    }
+
+    /**
+     * The `ForeignFutureFree` callback: Rust is done with the future, cancel the call if it's still running.
+     */
+    private static function free(): \Closure
+    {
+        return self::$free ??= static function (int $handle): void {
+            self::$calls->remove($handle)->cancel();
+        };
+    }
+{%- if config.async_runtime == AsyncRuntime::Fiber && features.fibers %}
+
+    /**
+     * Resume every call which is suspended. Called by `UniffiAsync` whenever it pauses.
+     */
+    public static function tick(): void
+    {
+        if (self::$calls === null) {
+            return;
+        }
+        foreach (self::$calls->values() as $call) {
+            $call->resume();
+        }
+    }
+{%- endif %}
+}
+
+/**
+ * A single call started by `UniffiForeignFutures`.
+ */
+final class UniffiForeignCall
+{
+    private bool $settled = false;
+
+    private \Closure $complete;
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+{%- if features.fibers %}
+
+    private ?\Fiber $fiber;
+
+    public function __construct(\Closure $complete, \Fiber $fiber)
+    {
+        $this->complete = $complete;
+        $this->fiber = $fiber;
+    }
+{%- else %}
+
+    public function __construct(\Closure $complete)
+    {
+        $this->complete = $complete;
+    }
+{%- endif %}
+{%- when AsyncRuntime::Amp %}
+
+    private \Amp\DeferredCancellation $cancellation;
+
+    public function __construct(\Closure $complete, \Amp\DeferredCancellation $cancellation)
+    {
+        $this->complete = $complete;
+        $this->cancellation = $cancellation;
+    }
+{%- when AsyncRuntime::React %}
+
+    private \React\Promise\PromiseInterface $promise;
+
+    public function __construct(\Closure $complete, \React\Promise\PromiseInterface $promise)
+    {
+        $this->complete = $complete;
+        $this->promise = $promise;
+    }
+{%- endmatch %}
+{%- if config.async_runtime == AsyncRuntime::Fiber && features.fibers %}
+
+    public function resume(): void
+    {
+        if ($this->fiber !== null && $this->fiber->isSuspended()) {
+            $fiber = $this->fiber;
+            $this->step(static fn () => $fiber->resume());
+        }
+    }
+
+    /**
+     * Run the fiber until it next suspends, completing the call if it finished.
+     */
+    public function step(\Closure $run): void
+    {
+        try {
+            $run();
+        } catch (\Throwable $e) {
+            $this->settle(static fn () => throw $e);
+            return;
+        }
+        // The fiber is gone if the call was cancelled while it ran.
+        if ($this->fiber?->isTerminated()) {
+            $value = $this->fiber->getReturn();
+            $this->settle(static fn () => $value);
+        }
+    }
+{%- endif %}
+
+    /**
+     * Report the outcome to Rust, unless the call was cancelled.
+     */
+    public function settle(\Closure $getValue): void
+    {
+        if ($this->settled) {
+            return;
+        }
+        $this->settled = true;
+{%- if config.async_runtime == AsyncRuntime::Fiber && features.fibers %}
+        $this->fiber = null;
+{%- endif %}
+        ($this->complete)($getValue);
+    }
+
+    public function cancel(): void
+    {
+        if ($this->settled) {
+            return;
+        }
+        $this->settled = true;
+{%- match config.async_runtime %}
+{%- when AsyncRuntime::Fiber %}
+{%- if features.fibers %}
+        // Dropping a suspended fiber unwinds it, running any `finally` blocks.
+        $this->fiber = null;
+{%- endif %}
+{%- when AsyncRuntime::Amp %}
+        $this->cancellation->cancel();
+{%- when AsyncRuntime::React %}
+        $this->promise->cancel();
+{%- endmatch %}
+    }
+}
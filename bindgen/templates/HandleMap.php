@@ -0,0 +1,49 @@
+/**
+ * A map from the opaque integer handles we pass to Rust back to the PHP values they stand for.
+ */
+final class UniffiHandleMap
+{
+    /** @var array<int, mixed> */
+    private array $map = [];
+
+    // Start at 1 so that a valid handle is never mistaken for a null pointer.
+    private int $counter = 1;
+
+    public function insert({% if features.mixed_type %}mixed {% endif %}$obj): int
+    {
+        $handle = $this->counter++;
+        $this->map[$handle] = $obj;
+        return $handle;
+    }
+
+    public function get(int $handle){% if features.mixed_type %}: mixed{% endif %}
+    {
+        if (!\array_key_exists($handle, $this->map)) {
+            throw new InternalException('UniffiHandleMap::get: Invalid handle');
+        }
+        return $this->map[$handle];
+    }
+
+    public function remove(int $handle){% if features.mixed_type %}: mixed{% endif %}
+    {
+        if (!\array_key_exists($handle, $this->map)) {
+            throw new InternalException('UniffiHandleMap::remove: Invalid handle');
+        }
+        $obj = $this->map[$handle];
+        unset($this->map[$handle]);
+        return $obj;
+    }
+
+    /**
+     * @return list<mixed>
+     */
+    public function values(): array
+    {
+        return \array_values($this->map);
+    }
+
+    public function count(): int
+    {
+        return \count($this->map);
+    }
+}
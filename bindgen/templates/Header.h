@@ -0,0 +1,12 @@
+#define FFI_SCOPE "{{ ffi_scope }}"
+#define FFI_LIB "{{ ffi_lib }}"
+
+// {{ crate::gen_php::GENERATED_HEADER }}
+
+// The FFI of the `{{ ci.namespace() }}` component, as declared to PHP's `\FFI`. Pass it to
+// `FFI::load()`, or preload it with opcache and get it back with `FFI::scope()`. The defines
+// have to come first, PHP only looks for them at the start of the file. Other than those,
+// `FFI::cdef()` gets the same declarations.
+
+{{ ffi_definitions }}
+
@@ -0,0 +1,134 @@
+// A handful of classes and functions to support the generated data structures.
+// This would be a good candidate for isolating in its own ffi-support lib.
+
+class InternalException extends \RuntimeException
+{
+}
+
+/**
+ * Thrown by an async call whose Rust future was cancelled before it completed.
+ */
+class CancelledException extends \RuntimeException
+{
+}
+{%- if self.has_unsupported() %}
+
+/**
+ * Thrown in place of calling a function or method the bindings don't support yet.
+ */
+class UnsupportedException extends \LogicException
+{
+}
+{%- endif %}
+
+final class UniffiRustCall
+{
+    // These match the values from the uniffi::rustcalls module
+    public const CALL_SUCCESS = 0;
+    public const CALL_ERROR = 1;
+    public const CALL_UNEXPECTED_ERROR = 2;
+    public const CALL_CANCELLED = 3;
+
+    /**
+     * Call a rust function which receives a `RustCallStatus*` as its last argument.
+     */
+    public static function call(\Closure $fn){% if features.mixed_type %}: mixed{% endif %}
+    {
+        return self::callWithError(null, $fn);
+    }
+
+    /**
+     * Call a rust function and handle any errors.
+     *
+     * This function is used for rust calls that return Result<> and therefore can set the CALL_ERROR status code.
+     * $errorConverter must be set to the FfiConverter class for the error that corresponds to the result.
+     */
+    public static function callWithError(?string $errorConverter, \Closure $fn){% if features.mixed_type %}: mixed{% endif %}
+    {
+        {%- if config.backend.is_extension() %}
+        $status = new UniffiRustCallStatus();
+        $result = $fn($status);
+        {%- else %}
+        $status = UniffiLib::ffi()->new('RustCallStatus');
+        $result = $fn(\FFI::addr($status));
+        {%- endif %}
+        self::checkCallStatus($errorConverter, $status);
+        return $result;
+    }
+
+    public static function checkCallStatus(?string $errorConverter, {{ config.backend.status_type() }} $status): void
+    {
+        switch ($status->code) {
+            case self::CALL_SUCCESS:
+                return;
+            case self::CALL_ERROR:
+                if ($errorConverter === null) {
+                    UniffiRustBuffer::free($status->errorBuf);
+                    throw new InternalException('UniffiRustCall::callWithError: CALL_ERROR, but $errorConverter is null');
+                }
+                throw $errorConverter::lift($status->errorBuf);
+            case self::CALL_UNEXPECTED_ERROR:
+                // When the rust code sees a panic, it tries to construct a RustBuffer
+                // with the message.  But if that code panics, then it just sends back
+                // an empty buffer.
+                {%- if config.backend.is_extension() %}
+                if (\strlen($status->errorBuf) > 0) {
+                {%- else %}
+                if ($status->errorBuf->len > 0) {
+                {%- endif %}
+                    throw new InternalException(FfiConverterString::lift($status->errorBuf));
+                }
+                throw new InternalException('Rust panic');
+            case self::CALL_CANCELLED:
+                throw new CancelledException('The Rust future was cancelled');
+            default:
+                throw new InternalException(\sprintf('Invalid RustCallStatus code: %d', $status->code));
+        }
+    }
+    {%- if !config.backend.is_extension() %}
+
+    /**
+     * Run a PHP implementation of a callback interface method on behalf of Rust,
+     * reporting any exception through the `RustCallStatus*` Rust handed us.
+     */
+    public static function traitInterfaceCall(
+        \FFI\CData $callStatus,
+        \Closure $makeCall,
+        \Closure $writeReturnValue,
+        ?string $errorClass = null,
+        ?string $errorConverter = null
+    ): void {
+        try {
+            $writeReturnValue($makeCall());
+        } catch (\Throwable $e) {
+            if ($errorClass !== null && $e instanceof $errorClass) {
+                $callStatus[0]->code = self::CALL_ERROR;
+                $callStatus[0]->errorBuf = $errorConverter::lower($e);
+                return;
+            }
+            $callStatus[0]->code = self::CALL_UNEXPECTED_ERROR;
+            $callStatus[0]->errorBuf = FfiConverterString::lower((string) $e);
+        }
+    }
+    {%- endif %}
+}
+{%- if config.backend.is_extension() %}
+
+/**
+ * The status of a Rust call, which the extension writes instead of a `RustCallStatus` struct.
+ */
+final class UniffiRustCallStatus
+{
+    /**
+     * @var int
+     */
+    public $code = 0;
+
+    /**
+     * The contents of the error `RustBuffer`, if any.
+     *
+     * @var string
+     */
+    public $errorBuf = '';
+}
+{%- endif %}
@@ -0,0 +1,23 @@
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): array
+    {
+        $count = $reader->readInt32();
+        $items = [];
+        for ($i = 0; $i < $count; $i++) {
+            $key = {{ key_type|read_fn }}($reader);
+            $items[$key] = {{ value_type|read_fn }}($reader);
+        }
+        return $items;
+    }
+
+    public static function write(array $value, UniffiByteWriter $writer): void
+    {
+        $writer->writeInt32(\count($value));
+        foreach ($value as $key => $item) {
+            {{ key_type|write_fn }}({{ key_type|map_key }}, $writer);
+            {{ value_type|write_fn }}($item, $writer);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+final class UniffiLib
+{
+    private static ?{{ ffi_class }} $ffi = null;
+    {%- if !config.backend.is_extension() %}
+
+    private const CDEF = <<<'CDEF'
+{{ ffi_definitions }}
+CDEF;
+    {%- endif %}
+
+    /**
+     * What the checksum function of each function, method and constructor has to return,
+     * for the library to have the interface the bindings were generated from.
+     */
+    private const CHECKSUMS = [
+        {%- for (name, checksum) in ci.iter_checksums() %}
+        '{{ name }}' => {{ checksum }},
+        {%- endfor %}
+    ];
+
+    /**
+     * The `\FFI` instance exposing the extern-C FFI definitions, loading the library on first use.
+     * This is an implementation detail which will be called internally by the public API.
+     */
+    public static function ffi(): {{ ffi_class }}
+    {
+        return self::$ffi ?? self::load(null);
+    }
+
+    {%- if config.backend.is_extension() %}
+
+    /**
+     * Check the library built into the `{{ extension_name }}` extension is the one the bindings
+     * were generated for. There's nothing to load, so `$path` is ignored.
+     */
+    {%- else %}
+
+    /**
+     * Load the library from `$path`, or find it ourselves, and check it's the one the bindings
+     * were generated for. Components built into the same library share its `\FFI`.
+     */
+    {%- endif %}
+    public static function load(?string $path): {{ ffi_class }}
+    {
+        if (self::$ffi !== null) {
+            throw new InternalException('The native library of `{{ ci.namespace() }}` is already loaded');
+        }
+        {%- if config.backend.is_extension() %}
+        if (!\extension_loaded('{{ extension_name }}')) {
+            throw new InternalException('The `{{ extension_name }}` extension isn\'t loaded');
+        }
+        $ffi = new {{ ffi_class }}();
+        {%- else %}
+        {%- match preload_scope %}
+        {%- when Some(scope) %}
+        $ffi = $path === null ? self::preloaded() : null;
+        $ffi = $ffi ?? \Uniffi\NativeLibraries::load('{{ config.cdylib_name() }}', $path ?? self::libraryPath(), self::CDEF);
+        {%- when None %}
+        $ffi = \Uniffi\NativeLibraries::load('{{ config.cdylib_name() }}', $path ?? self::libraryPath(), self::CDEF);
+        {%- endmatch %}
+        {%- endif %}
+        self::checkContractApiVersion($ffi);
+        self::checkApiChecksums($ffi);
+        self::$ffi = $ffi;
+        {%- for fn in self.initialization_fns() %}
+        {{ fn }}();
+        {%- endfor %}
+        return self::$ffi;
+    }
+    {%- match preload_scope %}
+    {%- when Some(scope) %}
+
+    /**
+     * The FFI declared by `preload.php`, if opcache preloaded it.
+     */
+    private static function preloaded(): ?\FFI
+    {
+        try {
+            return \FFI::scope('{{ scope }}');
+        } catch (\FFI\Exception $e) {
+            return null;
+        }
+    }
+    {%- when None %}
+    {%- endmatch %}
+    {%- if !config.backend.is_extension() %}
+
+    /**
+     * This is how we find and load the dynamic library provided by the component.
+     * For now we just look it up by name, next to this file.
+     */
+    private static function libraryPath(): string
+    {
+        $name = '{{ config.cdylib_name() }}';
+        {%- if features.php8_syntax %}
+        $filename = match (PHP_OS_FAMILY) {
+            'Darwin' => "lib{$name}.dylib",
+            'Windows' => "{$name}.dll",
+            default => "lib{$name}.so",
+        };
+        {%- else %}
+        switch (PHP_OS_FAMILY) {
+            case 'Darwin':
+                $filename = "lib{$name}.dylib";
+                break;
+            case 'Windows':
+                $filename = "{$name}.dll";
+                break;
+            default:
+                $filename = "lib{$name}.so";
+        }
+        {%- endif %}
+        return __DIR__ . DIRECTORY_SEPARATOR . $filename;
+    }
+    {%- endif %}
+
+    private static function checkContractApiVersion({{ ffi_class }} $ffi): void
+    {
+        // Get the bindings contract version from our ComponentInterface
+        $bindingsContractVersion = {{ ci.uniffi_contract_version() }};
+        // Get the scaffolding contract version by calling the into the dylib
+        $scaffoldingContractVersion = $ffi->{{ ci.ffi_uniffi_contract_version().name() }}();
+        if ($bindingsContractVersion !== $scaffoldingContractVersion) {
+            throw new InternalException('UniFFI contract version mismatch: try cleaning and rebuilding your project');
+        }
+    }
+
+    private static function checkApiChecksums({{ ffi_class }} $ffi): void
+    {
+        foreach (self::CHECKSUMS as $function => $checksum) {
+            if ($ffi->$function() !== $checksum) {
+                throw new InternalException('UniFFI API checksum mismatch: try cleaning and rebuilding your project');
+            }
+        }
+    }
+}
+
+/**
+ * Control over how the `{{ ci.namespace() }}` component loads its native library.
+ */
+final class Uniffi
+{
+    {%- if config.backend.is_extension() %}
+    /**
+     * Check the library built into the `{{ extension_name }}` extension, which the bindings
+     * otherwise do the first time it's needed. The extension has nothing to load, so `$libPath`
+     * is ignored.
+     */
+    {%- else %}
+    /**
+     * Load the native library from `$libPath`, instead of looking for it next to the bindings
+     * the first time it's needed. This has to come before anything else uses the component.
+     */
+    {%- endif %}
+    public static function init(string $libPath): void
+    {
+        UniffiLib::load($libPath);
+    }
+}
@@ -0,0 +1,62 @@
+// Each component declares these, so only the first one loaded gets to.
+if (!\interface_exists(Disposable::class, false)) {
+    /**
+     * An object backed by a Rust object, which can be freed before PHP collects it.
+     */
+    interface Disposable
+    {
+        public function destroy(): void;
+    }
+}
+
+if (!\class_exists(UseAfterFreeException::class, false)) {
+    /**
+     * Thrown when calling a method on an object after its `destroy()` method was called.
+     */
+    class UseAfterFreeException extends \LogicException
+    {
+    }
+}
+
+if (!\class_exists(NativeLibraries::class, false)) {
+    /**
+     * The `\FFI` of each native library loaded so far, by name. Components built into the same
+     * library declare all of its FFI, so they share one, and with it the C types they pass each
+     * other. The first of them to be loaded opens the library, wherever their bindings are.
+     */
+    final class NativeLibraries
+    {
+        /**
+         * @var array<string, \FFI>
+         */
+        private static $ffis = [];
+
+        public static function load(string $name, string $path, string $cdef): \FFI
+        {
+            if (!isset(self::$ffis[$name])) {
+                self::$ffis[$name] = \FFI::cdef($cdef, $path);
+            }
+            return self::$ffis[$name];
+        }
+    }
+}
+
+if (!\function_exists(__NAMESPACE__ . '\using')) {
+    /**
+     * Call `$fn` with `$obj`, destroying `$obj` once it returns or throws.
+     *
+     * @template T of Disposable
+     * @template R
+     * @param T $obj
+     * @param callable(T): R $fn
+     * @return R
+     */
+    function using(Disposable $obj, callable $fn){% if features.mixed_type %}: mixed{% endif %}
+    {
+        try {
+            return $fn($obj);
+        } finally {
+            $obj->destroy();
+        }
+    }
+}
@@ -1,3 +1,193 @@
-class Foo
+{%- let obj = ci.get_object_definition(name).unwrap() %}
+{%- let (interface_name, impl_class_name) = obj|object_names %}
+{%- let is_error = ci.is_name_used_as_error(name) %}
+{%- if obj.has_callback_interface() %}
+{%- if self.include_once_check("HandleMap.php") %}{% include "HandleMap.php" %}{% endif %}
+{%- endif %}
+{% call php::docstring(obj, 0) %}
+interface {{ interface_name }}
 {
-}
\ No newline at end of file
+    {%- for meth in obj.methods()|included_methods(name) %}
+    {%- if !loop.first %}
+{% endif %}
+    {%- match meth.name()|unsupported(name) %}
+    {%- when Some(message) %}
+    {%- call php::unsupported_docstring(meth, message, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}(...$args){% call php::never_type() %};
+    {%- when None %}
+    {%- call php::fn_docstring(meth, true, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}({% call php::arg_list_decl(meth) %}): {% call php::return_type_decl(meth) %};
+    {%- endmatch %}
+    {%- endfor %}
+}
+{% call php::docstring(obj, 0) %}
+class {{ impl_class_name }} {% if is_error %}extends \Exception {% endif %}implements {{ interface_name }}, \Uniffi\Disposable
+{
+    private ?{{ config.backend.pointer_type() }} $pointer = null;
+
+    {%- match obj.primary_constructor()|included_constructor(name) %}
+    {%- when Some with (cons) %}
+    {%- match cons.name()|unsupported(name) %}
+    {%- when Some(message) %}
+{% call php::unsupported_docstring(cons, message, 4) %}
+    public function __construct(...$args)
+    {
+        throw new UnsupportedException({{ message|php_string }});
+    }
+    {%- when None %}
+{% call php::fn_docstring(cons, false, 4) %}
+    public function __construct({% call php::arg_list_decl(cons) %})
+    {
+        {%- if is_error %}
+        parent::__construct();
+        {%- endif %}
+        $this->pointer = {% call php::to_ffi_call(cons) %};
+    }
+    {%- endmatch %}
+    {%- when None %}
+
+    private function __construct()
+    {
+    }
+    {%- endmatch %}
+
+    public function __destruct()
+    {
+        $this->destroy();
+    }
+
+    /**
+     * Free the Rust object now, rather than whenever PHP gets round to collecting this one.
+     *
+     * Calling this more than once is fine, but calling any other method afterwards throws
+     * `\Uniffi\UseAfterFreeException`.
+     */
+    public function destroy(): void
+    {
+        if ($this->pointer === null) {
+            return;
+        }
+        $pointer = $this->pointer;
+        $this->pointer = null;
+        UniffiRustCall::call(
+            fn ({{ config.backend.status_type() }} $uniffiStatus) => UniffiLib::ffi()->{{ obj.ffi_object_free().name() }}($pointer, $uniffiStatus)
+        );
+    }
+
+    /**
+     * Wrap a pointer handed to us by Rust, without calling the primary constructor.
+     */
+    public static function uniffiFromPointer({{ config.backend.pointer_type() }} $pointer): {% call php::static_type() %}
+    {
+        $instance = (new \ReflectionClass(static::class))->newInstanceWithoutConstructor();
+        $instance->pointer = $pointer;
+        return $instance;
+    }
+
+    public function uniffiClonePointer(): {{ config.backend.pointer_type() }}
+    {
+        if ($this->pointer === null) {
+            throw new \Uniffi\UseAfterFreeException(
+                \sprintf('%s used after destroy() was called', static::class)
+            );
+        }
+        $pointer = $this->pointer;
+        return UniffiRustCall::call(
+            fn ({{ config.backend.status_type() }} $uniffiStatus) => UniffiLib::ffi()->{{ obj.ffi_object_clone().name() }}($pointer, $uniffiStatus)
+        );
+    }
+    {%- for cons in obj.alternate_constructors()|included_constructors(name) %}
+    {%- match cons.name()|unsupported(name) %}
+    {%- when Some(message) %}
+{% call php::unsupported_docstring(cons, message, 4) %}
+    public static function {{ cons.name()|member_method_name(name) }}(...$args){% call php::never_type() %}
+    {
+        throw new UnsupportedException({{ message|php_string }});
+    }
+    {%- when None %}
+{% call php::fn_docstring(cons, true, 4) %}
+    public static function {{ cons.name()|member_method_name(name) }}({% call php::arg_list_decl(cons) %}): {% match config.async_runtime.awaitable_type() %}{% when Some(awaitable) %}{% if cons.is_async() %}{{ awaitable }}{% else %}{% call php::static_type() %}{% endif %}{% when None %}{% call php::static_type() %}{% endmatch %}
+    {
+        {%- if cons.is_async() %}
+        return {% call php::async_call(cons, "", "        ") %};
+        {%- else %}
+        return static::uniffiFromPointer({% call php::to_ffi_call(cons) %});
+        {%- endif %}
+    }
+    {%- endmatch %}
+    {%- endfor %}
+    {%- for meth in obj.methods()|included_methods(name) %}
+    {%- match meth.name()|unsupported(name) %}
+    {%- when Some(message) %}
+{% call php::unsupported_docstring(meth, message, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}(...$args){% call php::never_type() %}
+    {
+        throw new UnsupportedException({{ message|php_string }});
+    }
+    {%- when None %}
+{% call php::method_decl(meth.name()|member_method_name(name), meth) %}
+    {%- endmatch %}
+    {%- endfor %}
+}
+
+final class {{ ffi_converter_name }}
+{
+    {%- if obj.has_callback_interface() %}
+    private static UniffiHandleMap $handleMap;
+    {%- endif %}
+
+    public static function lift({{ config.backend.pointer_type() }} $pointer): {{ type_name }}
+    {
+        return {{ impl_class_name }}::uniffiFromPointer($pointer);
+    }
+
+    public static function lower({{ type_name }} $value): {{ config.backend.pointer_type() }}
+    {
+        {%- if obj.has_callback_interface() %}
+        // Rust always treats the pointer of a foreign-implementable trait as a handle into our map.
+        return UniffiLib::ffi()->cast('void *', self::$handleMap->insert($value));
+        {%- else %}
+        return $value->uniffiClonePointer();
+        {%- endif %}
+    }
+
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        {%- if config.backend.is_extension() %}
+        return self::lift($reader->readUInt64());
+        {%- else %}
+        return self::lift(UniffiLib::ffi()->cast('void *', $reader->readUInt64()));
+        {%- endif %}
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        {%- if config.backend.is_extension() %}
+        $writer->writeUInt64(self::lower($value));
+        {%- else %}
+        $writer->writeUInt64(UniffiLib::ffi()->cast('uintptr_t', self::lower($value))->cdata);
+        {%- endif %}
+    }
+    {%- if obj.has_callback_interface() %}
+    {%- let vtable = obj.vtable().expect("trait interface should have a vtable") %}
+    {%- let vtable_type_name = vtable|ffi_type_name %}
+    {%- let vtable_methods = obj.vtable_methods() %}
+    {%- let ffi_init_callback = obj.ffi_init_callback() %}
+    {%- include "CallbackInterfaceImpl.php" %}
+    {%- endif %}
+}
+{%- if is_error %}
+
+final class {{ ffi_converter_name }}__as_error extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        return {{ ffi_converter_name }}::read($reader);
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        {{ ffi_converter_name }}::write($value, $writer);
+    }
+}
+{%- endif %}
@@ -0,0 +1,21 @@
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        if ($reader->readInt8() === 0) {
+            return null;
+        }
+        return {{ inner_type|read_fn }}($reader);
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        if ($value === null) {
+            $writer->writeInt8(0);
+            return;
+        }
+        $writer->writeInt8(1);
+        {{ inner_type|write_fn }}($value, $writer);
+    }
+}
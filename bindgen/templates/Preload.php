@@ -0,0 +1,13 @@
+<?php
+
+// {{ crate::gen_php::GENERATED_HEADER }}
+
+// Point `opcache.preload` at this file to declare the FFI of the bindings once, when PHP
+// starts, rather than on every request. The bindings look for it with `FFI::scope()`, and
+// declare it themselves when it isn't there. This needs `ffi.enable` to be `preload` (the
+// default) or `1`, and the dynamic linker to find the `FFI_LIB` of each header, which the
+// `ffi_lib` config can make an absolute path.
+{% for header in headers %}
+\FFI::load(__DIR__ . DIRECTORY_SEPARATOR . '{{ header }}');
+{%- endfor %}
+
@@ -0,0 +1,23 @@
+
+final class {{ ffi_converter_name }}
+{
+    public static function lift({{ type_name }} $value): {{ type_name }}
+    {
+        return $value;
+    }
+
+    public static function lower({{ type_name }} $value): {{ type_name }}
+    {
+        return $value;
+    }
+
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        return $reader->read{{ canonical_type_name }}();
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        $writer->write{{ canonical_type_name }}($value);
+    }
+}
@@ -0,0 +1,49 @@
+{%- let rec = ci.get_record_definition(name).unwrap() %}
+{% call php::docstring(rec, 0) %}
+class {{ type_name }}
+{
+    {%- if features.php8_syntax %}
+    public function __construct(
+        {%- for field in rec.fields() %}
+        {%- call php::field_docstring(field, 8) %}
+        public {{ field|type_name }} ${{ field.name()|field_name(name) }}{{ rec.fields()|param_default(loop.index0) }},
+        {%- endfor %}
+    ) {
+    }
+    {%- else %}
+    {%- for field in rec.fields() %}
+    {%- call php::field_docstring(field, 4) %}
+    public {{ field|type_name }} ${{ field.name()|field_name(name) }};
+    {%- endfor %}
+{% call php::fields_docstring(rec.fields(), name, false, 4) %}
+    public function __construct(
+        {%- for field in rec.fields() %}
+        {{ field|type_name }} ${{ field.name()|field_name(name) }}{{ rec.fields()|param_default(loop.index0) }}
+        {%- if !loop.last %},{% endif %}
+        {%- endfor %}
+    ) {
+        {%- for field in rec.fields() %}
+        $this->{{ field.name()|field_name(name) }} = ${{ field.name()|field_name(name) }};
+        {%- endfor %}
+    }
+    {%- endif %}
+}
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): {{ type_name }}
+    {
+        return new {{ type_name }}(
+            {%- for field in rec.fields() %}
+            {{ field|read_fn }}($reader),
+            {%- endfor %}
+        );
+    }
+
+    public static function write({{ type_name }} $value, UniffiByteWriter $writer): void
+    {
+        {%- for field in rec.fields() %}
+        {{ field|write_fn }}($value->{{ field.name()|field_name(name) }}, $writer);
+        {%- endfor %}
+    }
+}
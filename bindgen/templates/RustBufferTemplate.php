@@ -0,0 +1,253 @@
+{% if config.backend.is_extension() -%}
+final class UniffiRustBuffer
+{
+    // The extension copies RustBuffers to and from strings, so there's nothing left to do here.
+
+    public static function fromBytes(string $bytes): string
+    {
+        return $bytes;
+    }
+
+    public static function consume(string $buf): string
+    {
+        return $buf;
+    }
+
+    public static function free(string $buf): void
+    {
+    }
+}
+{%- else -%}
+final class UniffiRustBuffer
+{
+    public static function empty(): \FFI\CData
+    {
+        return UniffiLib::ffi()->new('RustBuffer');
+    }
+
+    public static function alloc(int $size): \FFI\CData
+    {
+        return UniffiRustCall::call(
+            fn (\FFI\CData $uniffiStatus) => UniffiLib::ffi()->{{ ci.ffi_rustbuffer_alloc().name() }}($size, $uniffiStatus)
+        );
+    }
+
+    public static function free(\FFI\CData $buf): void
+    {
+        UniffiRustCall::call(
+            fn (\FFI\CData $uniffiStatus) => UniffiLib::ffi()->{{ ci.ffi_rustbuffer_free().name() }}($buf, $uniffiStatus)
+        );
+    }
+
+    /**
+     * Copy a PHP string into a newly allocated RustBuffer.
+     */
+    public static function fromBytes(string $bytes): \FFI\CData
+    {
+        $len = \strlen($bytes);
+        $buf = self::alloc($len);
+        if ($len > 0) {
+            \FFI::memcpy($buf->data, $bytes, $len);
+        }
+        $buf->len = $len;
+        return $buf;
+    }
+
+    /**
+     * Copy the contents of a RustBuffer into a PHP string and free the buffer.
+     */
+    public static function consume(\FFI\CData $buf): string
+    {
+        try {
+            return $buf->len > 0 ? \FFI::string($buf->data, $buf->len) : '';
+        } finally {
+            self::free($buf);
+        }
+    }
+}
+{%- endif %}
+
+/**
+ * Helper for structured reading of bytes from a RustBuffer.
+ */
+final class UniffiByteReader
+{
+    private string $bytes;
+
+    private int $offset = 0;
+
+    public function __construct(string $bytes)
+    {
+        $this->bytes = $bytes;
+    }
+
+    public function remaining(): int
+    {
+        return \strlen($this->bytes) - $this->offset;
+    }
+
+    public function read(int $size): string
+    {
+        if ($size > $this->remaining()) {
+            throw new InternalException('read past end of rust buffer');
+        }
+        $data = \substr($this->bytes, $this->offset, $size);
+        $this->offset += $size;
+        return $data;
+    }
+{% if !features.union_types %}
+    /**
+     * @return int|float
+     */
+{%- endif %}
+    private function unpack(string $format, int $size){% if features.union_types %}: int|float{% endif %}
+    {
+        return \unpack($format, $this->read($size))[1];
+    }
+
+    public function readInt8(): int
+    {
+        return $this->unpack('c', 1);
+    }
+
+    public function readUInt8(): int
+    {
+        return $this->unpack('C', 1);
+    }
+
+    public function readInt16(): int
+    {
+        $v = $this->unpack('n', 2);
+        return $v >= 0x8000 ? $v - 0x10000 : $v;
+    }
+
+    public function readUInt16(): int
+    {
+        return $this->unpack('n', 2);
+    }
+
+    public function readInt32(): int
+    {
+        $v = $this->unpack('N', 4);
+        return $v >= 0x80000000 ? $v - 0x100000000 : $v;
+    }
+
+    public function readUInt32(): int
+    {
+        return $this->unpack('N', 4);
+    }
+
+    public function readInt64(): int
+    {
+        return $this->unpack('J', 8);
+    }
+
+    // PHP has no unsigned 64-bit integer, values above PHP_INT_MAX wrap around.
+    public function readUInt64(): int
+    {
+        return $this->unpack('J', 8);
+    }
+
+    public function readFloat32(): float
+    {
+        return $this->unpack('G', 4);
+    }
+
+    public function readFloat64(): float
+    {
+        return $this->unpack('E', 8);
+    }
+}
+
+/**
+ * Helper for structured writing of bytes into a RustBuffer.
+ */
+final class UniffiByteWriter
+{
+    private string $bytes = '';
+
+    public function bytes(): string
+    {
+        return $this->bytes;
+    }
+
+    public function write(string $data): void
+    {
+        $this->bytes .= $data;
+    }
+
+    public function writeInt8(int $v): void
+    {
+        $this->bytes .= \pack('c', $v);
+    }
+
+    public function writeUInt8(int $v): void
+    {
+        $this->bytes .= \pack('C', $v);
+    }
+
+    public function writeInt16(int $v): void
+    {
+        $this->bytes .= \pack('n', $v);
+    }
+
+    public function writeUInt16(int $v): void
+    {
+        $this->bytes .= \pack('n', $v);
+    }
+
+    public function writeInt32(int $v): void
+    {
+        $this->bytes .= \pack('N', $v);
+    }
+
+    public function writeUInt32(int $v): void
+    {
+        $this->bytes .= \pack('N', $v);
+    }
+
+    public function writeInt64(int $v): void
+    {
+        $this->bytes .= \pack('J', $v);
+    }
+
+    public function writeUInt64(int $v): void
+    {
+        $this->bytes .= \pack('J', $v);
+    }
+
+    public function writeFloat32(float $v): void
+    {
+        $this->bytes .= \pack('G', $v);
+    }
+
+    public function writeFloat64(float $v): void
+    {
+        $this->bytes .= \pack('E', $v);
+    }
+}
+
+/**
+ * Base class for FfiConverters of types which are passed across the FFI in a RustBuffer.
+ *
+ * Subclasses provide static `read()` and `write()` methods.
+ */
+abstract class UniffiFfiConverterRustBuffer
+{
+    public static function lift({{ config.backend.buffer_type() }} $buf){% if features.mixed_type %}: mixed{% endif %}
+    {
+        $reader = new UniffiByteReader(UniffiRustBuffer::consume($buf));
+        $value = static::read($reader);
+        if ($reader->remaining() !== 0) {
+            throw new InternalException('junk data left in buffer after lifting');
+        }
+        return $value;
+    }
+
+    public static function lower({% if features.mixed_type %}mixed {% endif %}$value): {{ config.backend.buffer_type() }}
+    {
+        $writer = new UniffiByteWriter();
+        static::write($value, $writer);
+        return UniffiRustBuffer::fromBytes($writer->bytes());
+    }
+}
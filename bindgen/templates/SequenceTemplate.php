@@ -0,0 +1,21 @@
+
+final class {{ ffi_converter_name }} extends UniffiFfiConverterRustBuffer
+{
+    public static function read(UniffiByteReader $reader): array
+    {
+        $count = $reader->readInt32();
+        $items = [];
+        for ($i = 0; $i < $count; $i++) {
+            $items[] = {{ inner_type|read_fn }}($reader);
+        }
+        return $items;
+    }
+
+    public static function write(array $value, UniffiByteWriter $writer): void
+    {
+        $writer->writeInt32(\count($value));
+        foreach ($value as $item) {
+            {{ inner_type|write_fn }}($item, $writer);
+        }
+    }
+}
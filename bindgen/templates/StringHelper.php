@@ -0,0 +1,25 @@
+
+final class {{ ffi_converter_name }}
+{
+    public static function lift({{ config.backend.buffer_type() }} $buf): string
+    {
+        return UniffiRustBuffer::consume($buf);
+    }
+
+    public static function lower(string $value): {{ config.backend.buffer_type() }}
+    {
+        return UniffiRustBuffer::fromBytes($value);
+    }
+
+    public static function read(UniffiByteReader $reader): string
+    {
+        $size = $reader->readInt32();
+        return $reader->read($size);
+    }
+
+    public static function write(string $value, UniffiByteWriter $writer): void
+    {
+        $writer->writeInt32(\strlen($value));
+        $writer->write($value);
+    }
+}
@@ -0,0 +1,33 @@
+{%- match func.name()|unsupported("") %}
+{%- when Some(message) %}
+{%- if config.facade_class().is_some() %}
+{%- call php::unsupported_docstring(func, message, 4) %}
+{{ indent }}public static function {{ func.name()|method_name }}(...$args){% call php::never_type() %}
+{%- else %}
+{%- call php::unsupported_docstring(func, message, 0) %}
+function {{ func.name()|fn_name }}(...$args){% call php::never_type() %}
+{%- endif %}
+{{ indent }}{
+{{ indent }}    throw new UnsupportedException({{ message|php_string }});
+{{ indent }}}
+{%- when None %}
+{%- if config.facade_class().is_some() %}
+{%- call php::fn_docstring(func, true, 4) %}
+{{ indent }}public static function {{ func.name()|method_name }}({% call php::arg_list_decl(func) %}): {% call php::return_type_decl(func) %}
+{%- else %}
+{%- call php::fn_docstring(func, true, 0) %}
+function {{ func.name()|fn_name }}({% call php::arg_list_decl(func) %}): {% call php::return_type_decl(func) %}
+{%- endif %}
+{{ indent }}{
+{%- if func.is_async() %}
+{{ indent }}    {% call php::return_kw(func) %}{% call php::async_call(func, "", format!("{}    ", indent)) %};
+{%- else %}
+{%-     match func.return_type() %}
+{%-         when Some with (return_type) %}
+{{ indent }}    return {{ return_type|lift_fn }}({% call php::to_ffi_call(func) %});
+{%-         when None %}
+{{ indent }}    {% call php::to_ffi_call(func) %};
+{%-     endmatch %}
+{%- endif %}
+{{ indent }}}
+{%- endmatch %}
@@ -1 +1,92 @@
+{%- import "macros.php" as php %}
+
+{%- for type_ in ci.iter_types() %}
+{%- if !(type_|is_omitted) %}
+{%- let type_name = type_|type_name %}
+{%- let ffi_converter_name = type_|ffi_converter_name %}
+{%- let canonical_type_name = type_|canonical_name %}
+
+{#
+ # Map `Type` instances to an include statement for that type.
+ #
+ # There is a companion match in `PHPCodeOracle::create_code_type()` which performs a similar function for the
+ # Rust code.
+ #
+ #   - When adding additional types here, make sure to also add a match arm to that function.
+ #   - To keep things manageable, let's try to limit ourselves to these 2 mega-matches
+ #}
+{%- match type_ %}
+
+{%- when Type::Boolean %}
+{%- include "BooleanHelper.php" %}
+
+{%- when Type::Int8 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::Int16 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::Int32 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::Int64 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::UInt8 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::UInt16 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::UInt32 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::UInt64 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::Float32 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::Float64 %}
+{%- include "PrimitiveHelper.php" %}
+
+{%- when Type::String %}
+{%- include "StringHelper.php" %}
+
+{%- when Type::Bytes %}
+{%- include "StringHelper.php" %}
+
+{%- when Type::Enum { name, module_path } %}
+{%- let e = ci.get_enum_definition(name).unwrap() %}
+{# For enums, there are either an error *or* an enum, they can't be both. #}
+{%- if ci.is_name_used_as_error(name) %}
+{%- include "ErrorTemplate.php" %}
+{%- else %}
+{%- include "EnumTemplate.php" %}
+{% endif %}
+
+{%- when Type::Record { name, module_path } %}
+{%- include "RecordTemplate.php" %}
+
+{%- when Type::Object { name, module_path, imp } %}
 {%- include "ObjectTemplate.php" %}
+
+{%- when Type::Optional { inner_type } %}
+{%- include "OptionalTemplate.php" %}
+
+{%- when Type::Sequence { inner_type } %}
+{%- include "SequenceTemplate.php" %}
+
+{%- when Type::Map { key_type, value_type } %}
+{%- include "MapTemplate.php" %}
+
+{%- when Type::CallbackInterface { name, module_path } %}
+{%- include "CallbackInterfaceTemplate.php" %}
+
+{%- when Type::Custom { name, module_path, builtin } %}
+{%- include "CustomType.php" %}
+
+{%- else %}
+{%- endmatch %}
+{%- endif %}
+{%- endfor %}
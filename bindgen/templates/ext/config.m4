@@ -0,0 +1,19 @@
+dnl {{ crate::gen_php::GENERATED_HEADER }}
+
+dnl Build with `phpize && ./configure --with-{{ extension.option_name }}=DIR && make`, where DIR
+dnl holds `lib{{ extension.library_name }}.a`, the Rust library built as a `staticlib`.
+
+PHP_ARG_WITH([{{ extension.option_name }}],
+  [for the {{ extension.ci.namespace() }} UniFFI component],
+  [AS_HELP_STRING([--with-{{ extension.option_name }}=DIR],
+    [Build the {{ extension.ci.namespace() }} component, linking lib{{ extension.library_name }}.a from DIR])])
+
+if test "$PHP_{{ extension.constant_prefix }}" != "no"; then
+  if test ! -f "$PHP_{{ extension.constant_prefix }}/lib{{ extension.library_name }}.a"; then
+    AC_MSG_ERROR([lib{{ extension.library_name }}.a not found in $PHP_{{ extension.constant_prefix }}])
+  fi
+  {{ extension.constant_prefix }}_SHARED_LIBADD="$PHP_{{ extension.constant_prefix }}/lib{{ extension.library_name }}.a -lpthread -ldl -lm"
+  PHP_SUBST([{{ extension.constant_prefix }}_SHARED_LIBADD])
+  PHP_NEW_EXTENSION([{{ extension.extension_name }}], [{{ extension.extension_name }}.c], [$ext_shared])
+fi
+
@@ -0,0 +1,105 @@
+// {{ crate::gen_php::GENERATED_HEADER }}
+
+// A PHP extension exposing the FFI of the `{{ ci.namespace() }}` component, for when `ext-ffi`
+// isn't available. The bindings call the methods of its `UniffiNative` class instead of `\FFI`,
+// passing pointers as ints and `RustBuffer`s as strings.
+
+#ifdef HAVE_CONFIG_H
+#include "config.h"
+#endif
+
+#include <stdint.h>
+
+#include "php.h"
+#include "ext/standard/info.h"
+#include "php_{{ extension_name }}.h"
+
+{{ ffi_definitions }}
+
+static zend_class_entry *uniffi_native_ce;
+
+// Copy a PHP string into a new `RustBuffer`, which the function we pass it to takes over.
+static RustBuffer uniffi_lower_buffer(zend_string *str)
+{
+    RustCallStatus status = {0};
+    ForeignBytes bytes = {(int32_t) ZSTR_LEN(str), (const uint8_t *) ZSTR_VAL(str)};
+    return {{ ci.ffi_rustbuffer_from_bytes().name() }}(bytes, &status);
+}
+
+// Copy a `RustBuffer` into a PHP string and free the buffer.
+static zend_string *uniffi_lift_buffer(RustBuffer buf)
+{
+    RustCallStatus status = {0};
+    zend_string *str = buf.len > 0
+        ? zend_string_init((const char *) buf.data, (size_t) buf.len, 0)
+        : ZSTR_EMPTY_ALLOC();
+    {{ ci.ffi_rustbuffer_free().name() }}(buf, &status);
+    return str;
+}
+
+// Hand the status of a call to the `UniffiRustCallStatus` the bindings passed in.
+static void uniffi_write_status(zval *uniffi_status, RustCallStatus *status)
+{
+    zend_object *obj = Z_OBJ_P(uniffi_status);
+    zend_update_property_long(obj->ce, obj, ZEND_STRL("code"), status->code);
+    if (status->code != 0) {
+        zend_string *error = uniffi_lift_buffer(status->errorBuf);
+        zend_update_property_str(obj->ce, obj, ZEND_STRL("errorBuf"), error);
+        zend_string_release(error);
+    }
+}
+{%- for method in methods %}
+
+ZEND_BEGIN_ARG_INFO_EX(arginfo_{{ method.name }}, 0, 0, {{ method.arg_names.len() }})
+{%- for arg in method.arg_names %}
+    ZEND_ARG_INFO(0, {{ arg }})
+{%- endfor %}
+ZEND_END_ARG_INFO()
+
+PHP_METHOD(UniffiNative, {{ method.name }})
+{
+{{ method.body -}}
+}
+{%- endfor %}
+
+static const zend_function_entry uniffi_native_methods[] = {
+{%- for method in methods %}
+    PHP_ME(UniffiNative, {{ method.name }}, arginfo_{{ method.name }}, ZEND_ACC_PUBLIC)
+{%- endfor %}
+    PHP_FE_END
+};
+
+PHP_MINIT_FUNCTION({{ extension_name }})
+{
+    zend_class_entry ce;
+    INIT_NS_CLASS_ENTRY(ce, "{{ class_namespace }}", "UniffiNative", uniffi_native_methods);
+    uniffi_native_ce = zend_register_internal_class(&ce);
+    uniffi_native_ce->ce_flags |= ZEND_ACC_FINAL;
+    return SUCCESS;
+}
+
+PHP_MINFO_FUNCTION({{ extension_name }})
+{
+    php_info_print_table_start();
+    php_info_print_table_row(2, "UniFFI component", "{{ ci.namespace() }}");
+    php_info_print_table_row(2, "Version", PHP_{{ constant_prefix }}_VERSION);
+    php_info_print_table_end();
+}
+
+zend_module_entry {{ extension_name }}_module_entry = {
+    STANDARD_MODULE_HEADER,
+    "{{ extension_name }}",
+    NULL,
+    PHP_MINIT({{ extension_name }}),
+    NULL,
+    NULL,
+    NULL,
+    PHP_MINFO({{ extension_name }}),
+    PHP_{{ constant_prefix }}_VERSION,
+    STANDARD_MODULE_PROPERTIES
+};
+
+#ifdef COMPILE_DL_{{ constant_prefix }}
+ZEND_GET_MODULE({{ extension_name }})
+#endif
+
@@ -0,0 +1,12 @@
+// {{ crate::gen_php::GENERATED_HEADER }}
+
+#ifndef PHP_{{ extension.constant_prefix }}_H
+#define PHP_{{ extension.constant_prefix }}_H
+
+extern zend_module_entry {{ extension.extension_name }}_module_entry;
+#define phpext_{{ extension.extension_name }}_ptr &{{ extension.extension_name }}_module_entry
+
+#define PHP_{{ extension.constant_prefix }}_VERSION "{{ extension.version }}"
+
+#endif
+
@@ -0,0 +1,290 @@
+{#
+// Template to call into rust. Used in several places.
+// Variable names in `arg_list_decl` should match up with arg lists
+// passed to rust via `arg_list_lowered`
+#}
+
+{%- macro to_ffi_call(func) -%}
+{%- call _to_ffi_call_with_prefix_arg("", func) %}
+{%- endmacro -%}
+
+{%- macro to_ffi_call_with_prefix(prefix, func) -%}
+{%- call _to_ffi_call_with_prefix_arg(format!("{}, ", prefix), func) %}
+{%- endmacro -%}
+
+{%- macro _to_ffi_call_with_prefix_arg(prefix, func) -%}
+{%- match func.throws_type() -%}
+{%-     when Some with (e) -%}
+UniffiRustCall::callWithError({{ e|ffi_error_converter_name }}::class, {# -#}
+{%- else -%}
+UniffiRustCall::call({# -#}
+{%- endmatch -%}
+fn ({{ config.backend.status_type() }} $uniffiStatus) => UniffiLib::ffi()->{{ func.ffi_func().name() }}(
+    {{- prefix }}
+    {%- call arg_list_lowered(func) -%}
+    $uniffiStatus))
+{%- endmacro -%}
+
+{%- macro arg_list_lowered(func) %}
+    {%- for arg in func.arguments() -%}
+        {{ arg|lower_fn }}(${{ arg.name()|arg_name }}), {# -#}
+    {%- endfor %}
+{%- endmacro -%}
+
+{%- macro arg_list_lowered_async(func) %}
+    {%- for arg in func.arguments() -%}
+        {{ arg|lower_fn }}(${{ arg.name()|arg_name }})
+        {%- if !loop.last %}, {% endif %}
+    {%- endfor %}
+{%- endmacro -%}
+
+{#-
+// Arglist as used in PHP declarations of methods, functions and constructors.
+// Note the type_name filters.
+-#}
+
+{% macro arg_list_decl(func) %}
+    {%- for arg in func.arguments() -%}
+        {{ arg|type_name }} ${{ arg.name()|arg_name }}{{ func.arguments()|param_default(loop.index0) }}
+        {%- if !loop.last %}, {% endif -%}
+    {%- endfor %}
+    {%- if func.is_async() && config.async_runtime == AsyncRuntime::Amp -%}
+        {%- if !func.arguments().is_empty() %}, {% endif -%}
+        ?\Amp\Cancellation $cancellation = null
+    {%- endif %}
+{%- endmacro %}
+
+{#-
+// The PHP return type declaration of a function or method.
+-#}
+{%- macro return_type_decl(func) -%}
+{%- match config.async_runtime.awaitable_type() %}
+{%- when Some(awaitable) %}{% if func.is_async() %}{{ awaitable }}{% else %}{{ func.return_type()|return_type_name }}{% endif %}
+{%- when None %}{{ func.return_type()|return_type_name }}
+{%- endmatch %}
+{%- endmacro -%}
+
+{#-
+// The return type of methods returning an instance of the class they're called on.
+-#}
+{%- macro static_type() -%}
+{%- if features.static_return_type %}static{% else %}self{% endif %}
+{%- endmacro -%}
+
+{#-
+// The return type of the stubs throwing `UnsupportedException`, if there's one for them.
+-#}
+{%- macro never_type() -%}
+{%- if features.never_type %}: never{% endif %}
+{%- endmacro -%}
+
+{#-
+// `return ` if calling `func` produces a value: anything with a return type, and every async call
+// when async functions hand back an awaitable.
+-#}
+{%- macro return_kw(func) -%}
+{%- if func.return_type().is_some() || (func.is_async() && config.async_runtime.awaitable_type().is_some()) %}return {% endif %}
+{%- endmacro -%}
+
+{#
+ # Call an async Rust function: lower the arguments, start the RustFuture and hand it to `UniffiAsync`,
+ # which drives it to completion (or wraps it in an awaitable, depending on the async runtime).
+ # `indent` is the indentation of the line the call starts on.
+ #}
+{%- macro async_call(func, prefix, indent) -%}
+UniffiAsync::call(
+{{ indent }}    UniffiLib::ffi()->{{ func.ffi_func().name() }}({{ prefix }}{% if !prefix.is_empty() && !func.arguments().is_empty() %}, {% endif %}{% call arg_list_lowered_async(func) %}),
+{{ indent }}    '{{ func.ffi_rust_future_poll(ci) }}',
+{{ indent }}    '{{ func.ffi_rust_future_cancel(ci) }}',
+{{ indent }}    '{{ func.ffi_rust_future_complete(ci) }}',
+{{ indent }}    '{{ func.ffi_rust_future_free(ci) }}',
+    {%- match func.return_type() %}
+    {%- when Some(return_type) %}
+{{ indent }}    {% call converter_closure(return_type, "lift") %},
+    {%- when None %}
+{{ indent }}    null,
+    {%- endmatch %}
+    {%- match func.throws_type() %}
+    {%- when Some(e) %}
+{{ indent }}    {{ e|ffi_error_converter_name }}::class,
+    {%- when None %}
+{{ indent }}    null,
+    {%- endmatch %}
+    {%- if config.async_runtime == AsyncRuntime::Amp %}
+{{ indent }}    $cancellation,
+    {%- endif %}
+{{ indent }})
+{%- endmacro -%}
+
+{#-
+// A closure calling one of a type's converter methods, e.g. `FfiConverterString::lift(...)`.
+-#}
+{%- macro converter_closure(type_, method) -%}
+{%- if features.first_class_callables -%}
+{{ type_|ffi_converter_name }}::{{ method }}(...)
+{%- else -%}
+\Closure::fromCallable([{{ type_|ffi_converter_name }}::class, '{{ method }}'])
+{%- endif -%}
+{%- endmacro -%}
+
+{#-
+// The PHPDoc of a class, property, case or constant, from its docstring. Like the other PHPDoc
+// macros this starts with a newline, so call it at the end of the line before the declaration.
+-#}
+{%- macro docstring(defn, indent_spaces) %}
+{%- match defn.docstring() %}
+{%- when Some(docstring) %}
+{{ docstring|docstring(indent_spaces) }}
+{%- else %}
+{%- endmatch %}
+{%- endmacro %}
+
+{#-
+// The PHPDoc of a property: its docstring and, if PHP's type doesn't say it all, its `@var` type.
+-#}
+{%- macro field_docstring(field, indent_spaces) %}
+{%- let docstring = field.docstring() %}
+{%- let doc = field|var_docstring(docstring, indent_spaces) %}
+{%- if !doc.is_empty() %}
+{{ doc }}
+{%- endif %}
+{%- endmacro %}
+
+{#-
+// The `@return` type of a getter, where PHP's type doesn't say it all.
+-#}
+{%- macro getter_docstring(field, indent_spaces) %}
+{%- let doc = field|getter_docstring(indent_spaces) %}
+{%- if !doc.is_empty() %}
+{{ doc }}
+{%- endif %}
+{%- endmacro %}
+
+{#-
+// `@param` tags for a constructor taking `fields`, where PHP's types don't say it all. `parent`
+// is the record the fields belong to, if any.
+-#}
+{%- macro fields_docstring(fields, parent, in_exception, indent_spaces) %}
+{%- let doc = fields|fields_docstring(parent, in_exception, indent_spaces) %}
+{%- if !doc.is_empty() %}
+{{ doc }}
+{%- endif %}
+{%- endmacro %}
+
+{#-
+// The PHPDoc of a function, method or constructor: its docstring plus `@param`, `@return` and
+// `@throws` tags. `returns` is false for constructors called with `new`.
+-#}
+{%- macro fn_docstring(func, returns, indent_spaces) %}
+{%- let docstring = func.docstring() %}
+{%- let doc = func|fn_docstring(docstring, config, returns, indent_spaces) %}
+{%- if !doc.is_empty() %}
+{{ doc }}
+{%- endif %}
+{%- endmacro %}
+
+{#-
+// The PHPDoc of a stub standing in for a function, method or constructor the bindings can't call.
+// `message` is that of the `UnsupportedException` it throws.
+-#}
+{%- macro unsupported_docstring(func, message, indent_spaces) %}
+{%- let docstring = func.docstring() %}
+{{ docstring|unsupported_docstring(message, indent_spaces) }}
+{%- endmacro %}
+
+{#
+ # Macro to call methods
+ #}
+{%- macro method_decl(php_method_name, meth) %}
+{%- call fn_docstring(meth, true, 4) %}
+    public function {{ php_method_name }}({% call arg_list_decl(meth) %}): {% call return_type_decl(meth) %}
+    {
+{%- if meth.is_async() %}
+        {% call return_kw(meth) %}{% call async_call(meth, "$this->uniffiClonePointer()", "        ") %};
+{%- else %}
+{%-     match meth.return_type() %}
+{%-         when Some with (return_type) %}
+        return {{ return_type|lift_fn }}({% call to_ffi_call_with_prefix("$this->uniffiClonePointer()", meth) %});
+{%-         when None %}
+        {% call to_ffi_call_with_prefix("$this->uniffiClonePointer()", meth) %};
+{%-     endmatch %}
+{%- endif %}
+    }
+{% endmacro %}
+
+{#-
+// The properties of an enum or error variant, and its constructor up to the end of the property
+// assignments (callers close the body). Variants are immutable, so their fields are `readonly`
+// where PHP supports it, otherwise private with getters.
+// `in_readonly_class` is set when the class itself is declared `readonly`, and `in_exception` for
+// the variants of errors, whose fields are renamed where they'd clash with `\Exception`.
+-#}
+{%- macro variant_fields(fields, in_readonly_class, in_exception) %}
+{%- if features.readonly_properties %}
+    public function __construct(
+        {%- for field in fields %}
+        {%- call field_docstring(field, 8) %}
+        public {% if !in_readonly_class %}readonly {% endif %}{{ field|type_name }} ${% call field_name(field, loop.index0, in_exception) %},
+        {%- endfor %}
+    ) {
+{%- else %}
+    {%- for field in fields %}
+    {%- call field_docstring(field, 4) %}
+    private {{ field|type_name }} ${% call field_name(field, loop.index0, in_exception) %};
+    {%- endfor %}
+{% call fields_docstring(fields, "", in_exception, 4) %}
+    public function __construct(
+        {%- for field in fields %}
+        {{ field|type_name }} ${% call field_name(field, loop.index0, in_exception) %}{% if !loop.last %},{% endif %}
+        {%- endfor %}
+    ) {
+        {%- for field in fields %}
+        $this->{% call field_name(field, loop.index0, in_exception) %} = ${% call field_name(field, loop.index0, in_exception) %};
+        {%- endfor %}
+{%- endif %}
+{%- endmacro %}
+
+{#-
+// The getters of a variant's fields, when they aren't `readonly` properties.
+-#}
+{%- macro variant_getters(fields, in_exception) %}
+{%- if !features.readonly_properties %}
+    {%- for field in fields %}
+{% call getter_docstring(field, 4) %}
+    public function {% call getter_name(field, loop.index0, in_exception) %}(): {{ field|type_name }}
+    {
+        return $this->{% call field_name(field, loop.index0, in_exception) %};
+    }
+    {%- endfor %}
+{%- endif %}
+{%- endmacro %}
+
+{#-
+// Read the value of a variant's field.
+-#}
+{%- macro variant_field_value(field, field_num, in_exception) %}
+{%- if features.readonly_properties -%}
+{% call field_name(field, field_num, in_exception) %}
+{%- else -%}
+{% call getter_name(field, field_num, in_exception) %}()
+{%- endif -%}
+{%- endmacro %}
+
+{%- macro getter_name(field, field_num, in_exception) %}
+{%- if field.name().is_empty() -%}
+getV{{- field_num -}}
+{%- else -%}
+{{ field.name()|getter_name(in_exception) }}
+{%- endif -%}
+{%- endmacro %}
+
+{#-
+// The property name of a record/enum/error field; unnamed (tuple) fields are named by position.
+-#}
+{%- macro field_name(field, field_num, in_exception) %}
+{%- if field.name().is_empty() -%}
+v{{- field_num -}}
+{%- else -%}
+{{ field.name()|variant_field_name(in_exception) }}
+{%- endif -%}
+{%- endmacro %}
@@ -0,0 +1,11 @@
+{%- let cbi = ci.get_callback_interface_definition(name).unwrap() %}
+{% call php::docstring(cbi, 0) %}
+interface {{ type_name }}
+{
+    {%- for meth in cbi.methods() %}
+    {%- if !loop.first %}
+{% endif %}
+    {%- call php::fn_docstring(meth, true, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}({% call php::arg_list_decl(meth) %}): {% call php::return_type_decl(meth) %};
+    {%- endfor %}
+}
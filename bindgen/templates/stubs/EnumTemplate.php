@@ -0,0 +1,37 @@
+{%- if e.is_flat() %}
+{%- if features.native_enums %}
+{% call php::docstring(e, 0) %}
+enum {{ type_name }}: int
+{
+    {%- for variant in e.variants() %}
+    {%- call php::docstring(variant, 4) %}
+    case {{ variant.name()|enum_variant_php_quoted(name) }} = {{ e|variant_discr_literal(loop.index0) }};
+    {%- endfor %}
+}
+{%- else %}
+{% call php::docstring(e, 0) %}
+final class {{ name|class_name }}
+{
+    {%- for variant in e.variants() %}
+    {%- call php::docstring(variant, 4) %}
+    public const {{ variant.name()|enum_variant_php_quoted(name) }} = {{ e|variant_discr_literal(loop.index0) }};
+    {%- endfor %}
+}
+{%- endif %}
+{%- else %}
+{% call php::docstring(e, 0) %}
+abstract {% if features.readonly_classes %}readonly {% endif %}class {{ type_name }}
+{
+}
+{%- for variant in e.variants() %}
+{% call php::docstring(variant, 0) %}
+final {% if features.readonly_classes %}readonly {% endif %}class {{ type_name }}{{ variant.name()|variant_class_name(name) }} extends {{ type_name }}
+{
+    {%- if variant.has_fields() %}
+    {%- call php::variant_fields(variant.fields(), features.readonly_classes, false) %}
+    }
+    {%- call php::variant_getters(variant.fields(), false) %}
+    {%- endif %}
+}
+{%- endfor %}
+{%- endif %}
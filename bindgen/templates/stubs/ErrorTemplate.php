@@ -0,0 +1,16 @@
+
+{% call php::docstring(e, 0) %}
+class {{ type_name }} extends \Exception
+{
+}
+{%- for variant in e.variants() %}
+{% call php::docstring(variant, 0) %}
+final class {{ type_name }}{{ variant.name()|variant_class_name(name) }} extends {{ type_name }}
+{
+    {%- if !e.is_flat() && variant.has_fields() %}
+    {%- call php::variant_fields(variant.fields(), false, true) %}
+    }
+    {%- call php::variant_getters(variant.fields(), true) %}
+    {%- endif %}
+}
+{%- endfor %}
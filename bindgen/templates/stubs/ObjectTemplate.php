@@ -0,0 +1,78 @@
+{%- let obj = ci.get_object_definition(name).unwrap() %}
+{%- let (interface_name, impl_class_name) = obj|object_names %}
+{%- let is_error = ci.is_name_used_as_error(name) %}
+{% call php::docstring(obj, 0) %}
+interface {{ interface_name }}
+{
+    {%- for meth in obj.methods()|included_methods(name) %}
+    {%- if !loop.first %}
+{% endif %}
+    {%- match meth.name()|unsupported(name) %}
+    {%- when Some(message) %}
+    {%- call php::unsupported_docstring(meth, message, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}(...$args){% call php::never_type() %};
+    {%- when None %}
+    {%- call php::fn_docstring(meth, true, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}({% call php::arg_list_decl(meth) %}): {% call php::return_type_decl(meth) %};
+    {%- endmatch %}
+    {%- endfor %}
+}
+{% call php::docstring(obj, 0) %}
+class {{ impl_class_name }} {% if is_error %}extends \Exception {% endif %}implements {{ interface_name }}, \Uniffi\Disposable
+{
+    {%- match obj.primary_constructor()|included_constructor(name) %}
+    {%- when Some with (cons) %}
+    {%- match cons.name()|unsupported(name) %}
+    {%- when Some(message) %}
+    {%- call php::unsupported_docstring(cons, message, 4) %}
+    public function __construct(...$args)
+    {
+    }
+    {%- when None %}
+    {%- call php::fn_docstring(cons, false, 4) %}
+    public function __construct({% call php::arg_list_decl(cons) %})
+    {
+    }
+    {%- endmatch %}
+    {%- when None %}
+    private function __construct()
+    {
+    }
+    {%- endmatch %}
+
+    /**
+     * Free the Rust object now, rather than whenever PHP gets round to collecting this one.
+     *
+     * Calling this more than once is fine, but calling any other method afterwards throws
+     * `\Uniffi\UseAfterFreeException`.
+     */
+    public function destroy(): void
+    {
+    }
+    {%- for cons in obj.alternate_constructors()|included_constructors(name) %}
+    {%- match cons.name()|unsupported(name) %}
+    {%- when Some(message) %}
+{% call php::unsupported_docstring(cons, message, 4) %}
+    public static function {{ cons.name()|member_method_name(name) }}(...$args){% call php::never_type() %}
+    {
+    }
+    {%- when None %}
+{% call php::fn_docstring(cons, true, 4) %}
+    public static function {{ cons.name()|member_method_name(name) }}({% call php::arg_list_decl(cons) %}): {% match config.async_runtime.awaitable_type() %}{% when Some(awaitable) %}{% if cons.is_async() %}{{ awaitable }}{% else %}{% call php::static_type() %}{% endif %}{% when None %}{% call php::static_type() %}{% endmatch %}
+    {
+    }
+    {%- endmatch %}
+    {%- endfor %}
+    {%- for meth in obj.methods()|included_methods(name) %}
+    {%- match meth.name()|unsupported(name) %}
+    {%- when Some(message) %}
+{% call php::unsupported_docstring(meth, message, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}(...$args){% call php::never_type() %}
+    {%- when None %}
+{% call php::fn_docstring(meth, true, 4) %}
+    public function {{ meth.name()|member_method_name(name) }}({% call php::arg_list_decl(meth) %}): {% call php::return_type_decl(meth) %}
+    {%- endmatch %}
+    {
+    }
+    {%- endfor %}
+}
@@ -0,0 +1,136 @@
+<?php
+
+// {{ crate::gen_php::GENERATED_HEADER }}
+
+// IDE stubs for the `{{ ci.namespace() }}` bindings: every class and function they declare, with
+// its signature and documentation but none of the FFI plumbing behind it. Point your IDE or static
+// analyser at this file, and load `{{ module_name }}.php` at runtime.
+
+namespace Uniffi {
+
+/**
+ * An object backed by a Rust object, which can be freed before PHP collects it.
+ */
+interface Disposable
+{
+    public function destroy(): void;
+}
+
+/**
+ * Thrown when calling a method on an object after its `destroy()` method was called.
+ */
+class UseAfterFreeException extends \LogicException
+{
+}
+
+/**
+ * Call `$fn` with `$obj`, destroying `$obj` once it returns or throws.
+ *
+ * @template T of Disposable
+ * @template R
+ * @param T $obj
+ * @param callable(T): R $fn
+ * @return R
+ */
+function using(Disposable $obj, callable $fn){% if features.mixed_type %}: mixed{% endif %}
+{
+}
+
+}
+
+namespace {{ namespace }} {
+
+class InternalException extends \RuntimeException
+{
+}
+
+/**
+ * Thrown by an async call whose Rust future was cancelled before it completed.
+ */
+class CancelledException extends \RuntimeException
+{
+}
+{%- if self.has_unsupported() %}
+
+/**
+ * Thrown in place of calling a function or method the bindings don't support yet.
+ */
+class UnsupportedException extends \LogicException
+{
+}
+{%- endif %}
+
+/**
+ * Control over how the `{{ ci.namespace() }}` component loads its native library.
+ */
+final class Uniffi
+{
+    /**
+     * Load the native library from `$libPath`, instead of looking for it next to the bindings
+     * the first time it's needed. This has to come before anything else uses the component.
+     */
+    public static function init(string $libPath): void
+    {
+    }
+}
+
+{%- for type_ in ci.iter_types() %}
+{%- if !(type_|is_omitted) %}
+{%- let type_name = type_|type_name %}
+{%- match type_ %}
+{%- when Type::Enum { name, module_path } %}
+{%- let e = ci.get_enum_definition(name).unwrap() %}
+{%- if ci.is_name_used_as_error(name) %}
+{%- include "stubs/ErrorTemplate.php" %}
+{%- else %}
+{%- include "stubs/EnumTemplate.php" %}
+{%- endif %}
+{%- when Type::Record { name, module_path } %}
+{%- include "stubs/RecordTemplate.php" %}
+{%- when Type::Object { name, module_path, imp } %}
+{%- include "stubs/ObjectTemplate.php" %}
+{%- when Type::CallbackInterface { name, module_path } %}
+{%- include "stubs/CallbackInterfaceTemplate.php" %}
+{%- else %}
+{%- endmatch %}
+{%- endif %}
+{%- endfor %}
+
+{%- match config.facade_class() %}
+{%- when Some(facade_class) %}
+
+final class {{ facade_class }}
+{
+{%- for func in ci.function_definitions()|included_functions %}
+{%- if !loop.first %}
+{% endif %}
+{%- match func.name()|unsupported("") %}
+{%- when Some(message) %}
+{%- call php::unsupported_docstring(func, message, 4) %}
+    public static function {{ func.name()|method_name }}(...$args){% call php::never_type() %}
+{%- when None %}
+{%- call php::fn_docstring(func, true, 4) %}
+    public static function {{ func.name()|method_name }}({% call php::arg_list_decl(func) %}): {% call php::return_type_decl(func) %}
+{%- endmatch %}
+    {
+    }
+{%- endfor %}
+}
+{%- when None %}
+{%- for func in ci.function_definitions()|included_functions %}
+{%- match func.name()|unsupported("") %}
+{%- when Some(message) %}
+{% call php::unsupported_docstring(func, message, 0) %}
+function {{ func.name()|fn_name }}(...$args){% call php::never_type() %}
+{%- when None %}
+{% call php::fn_docstring(func, true, 0) %}
+function {{ func.name()|fn_name }}({% call php::arg_list_decl(func) %}): {% call php::return_type_decl(func) %}
+{%- endmatch %}
+{
+}
+{%- endfor %}
+{%- endmatch %}
+
+}
+
+{% import "macros.php" as php %}
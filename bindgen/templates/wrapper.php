@@ -1,7 +1,53 @@
 <?php
 
-{%- for obj in ci.object_definitions() %}
-"{{ obj|type_name }}"
+// {{ crate::gen_php::GENERATED_HEADER }}
+
+declare(strict_types=1);
+
+// Runtime support shared by every UniFFI component loaded into the process.
+// It has to be declared before the classes which use it.
+namespace Uniffi {
+
+{% include "ObjectRuntime.php" %}
+
+}
+
+namespace {{ namespace }} {
+
+{% include "Helpers.php" %}
+{% include "RustBufferTemplate.php" %}
+
+// Contains loading, initialization code, and the FFI Function declarations.
+{% include "NamespaceLibraryTemplate.php" %}
+
+{%- if ci.has_async_fns() && !config.backend.is_extension() %}
+
+// Async support
+{% include "Async.php" %}
+{%- endif %}
+
+// Public interface members begin here.
+{{ type_helper_code }}
+
+{%- match config.facade_class() %}
+{%- when Some(facade_class) %}
+{%- let indent = "    " %}
+
+final class {{ facade_class }}
+{
+{%- for func in ci.function_definitions()|included_functions %}
+{%- if !loop.first %}
+{% endif %}
+{% include "TopLevelFunctionTemplate.php" %}
+{%- endfor %}
+}
+{%- when None %}
+{%- let indent = "" %}
+{%- for func in ci.function_definitions()|included_functions %}
+{% include "TopLevelFunctionTemplate.php" %}
 {%- endfor %}
+{%- endmatch %}
+
+}
 
 {% import "macros.php" as php %}
@@ -1,4 +1,5 @@
 #!/bin/bash
 set -euxo pipefail
 
-cargo build --package uniffi-bindgen-php --package uniffi-bindgen-php-fixtures
+# The bindgen and every fixture, each in its own library and all of them in libuniffi_fixtures.
+cargo build --workspace
@@ -1,21 +1,67 @@
 #!/bin/bash
 set -euxo pipefail
 
+# Usage: ./build_bindings.sh [--combined] [FIXTURE...]
+#
+# Each fixture (a directory of fixtures/) builds its own cdylib and gets its own PHP package in
+# out/<crate>, with the library next to the bindings, so it can be tested on its own. Without
+# FIXTUREs, that's done for all of them.
+#
+# With --combined, the fixtures are built into the one libuniffi_fixtures instead, and the
+# bindings of all their components go in out/combined, to test components sharing a library.
+#
+# test_bindings.sh runs the PHP tests of fixtures/*/tests against these.
+
 SCRIPT_DIR="${SCRIPT_DIR:-$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )}"
 ROOT_DIR="$SCRIPT_DIR"
 
 BINDINGS_DIR="$ROOT_DIR/out"
 BINARIES_DIR="$ROOT_DIR/target/debug"
+BINDGEN="$BINARIES_DIR/uniffi-bindgen-php"
 
-rm -rf $BINDINGS_DIR
-mkdir $BINDINGS_DIR
+if [[ "$OSTYPE" == "darwin"* ]]; then
+LIB_EXT="dylib"
+else
+LIB_EXT="so"
+fi
 
-# FIXME: It would be better to generate and build fixtures one by one, instead of combining
-# them all into the same library
+COMBINED=false
+if [[ "${1:-}" == "--combined" ]]; then
+    COMBINED=true
+    shift
+fi
 
-if [[ "$OSTYPE" == "darwin"* ]]; then
-LIB_FILE="$BINARIES_DIR/libuniffi_fixtures.dylib"
-else 
-LIB_FILE="$BINARIES_DIR/libuniffi_fixtures.so"
+mkdir -p "$BINDINGS_DIR"
+cargo build --package uniffi-bindgen-php
+
+if $COMBINED; then
+    OUT_DIR="$BINDINGS_DIR/combined"
+    rm -rf "$OUT_DIR"
+    cargo build --package uniffi-bindgen-php-fixtures
+    "$BINDGEN" "$BINARIES_DIR/libuniffi_fixtures.$LIB_EXT" --out-dir "$OUT_DIR" --library
+    cp "$BINARIES_DIR/libuniffi_fixtures.$LIB_EXT" "$OUT_DIR/"
+    exit 0
+fi
+
+if [[ $# -gt 0 ]]; then
+    FIXTURES=("$@")
+else
+    FIXTURES=()
+    for dir in "$ROOT_DIR"/fixtures/*/; do
+        fixture="$(basename "$dir")"
+        if [[ "$fixture" != "combined" ]]; then
+            FIXTURES+=("$fixture")
+        fi
+    done
 fi
-target/debug/uniffi-bindgen-php $LIB_FILE --out-dir "$BINDINGS_DIR" --library --config "$ROOT_DIR/fixtures/uniffi.toml"
+
+for fixture in "${FIXTURES[@]}"; do
+    CRATE="uniffi_fixture_$fixture"
+    OUT_DIR="$BINDINGS_DIR/$CRATE"
+    rm -rf "$OUT_DIR"
+    cargo build --package "uniffi-fixture-$fixture"
+    # Finds the library and each crate's uniffi.toml through cargo metadata, and writes the
+    # bindings of each crate with components into out/<crate>.
+    "$BINDGEN" --manifest-path "$ROOT_DIR/fixtures/$fixture/Cargo.toml" --out-dir "$BINDINGS_DIR"
+    cp "$BINARIES_DIR/lib$CRATE.$LIB_EXT" "$OUT_DIR/"
+done
@@ -0,0 +1,38 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+//! A small proc-macro component, next to Loro's UDL one in the combined library.
+
+#[derive(Debug, thiserror::Error, uniffi::Error)]
+pub enum ArithmeticError {
+    #[error("Integer overflow on an operation with {a} and {b}")]
+    IntegerOverflow { a: u64, b: u64 },
+}
+
+#[uniffi::export]
+pub fn add(a: u64, b: u64) -> Result<u64, ArithmeticError> {
+    a.checked_add(b)
+        .ok_or(ArithmeticError::IntegerOverflow { a, b })
+}
+
+#[uniffi::export]
+pub fn sub(a: u64, b: u64) -> Result<u64, ArithmeticError> {
+    a.checked_sub(b)
+        .ok_or(ArithmeticError::IntegerOverflow { a, b })
+}
+
+#[uniffi::export]
+pub fn div(dividend: u64, divisor: u64) -> u64 {
+    if divisor == 0 {
+        panic!("Can't divide by zero");
+    }
+    dividend / divisor
+}
+
+#[uniffi::export]
+pub fn equal(a: u64, b: u64) -> bool {
+    a == b
+}
+
+uniffi::setup_scaffolding!("arithmetic");
@@ -0,0 +1,35 @@
+<?php
+
+// The arithmetic fixture, through the bindings in the directory given as the first argument.
+
+declare(strict_types=1);
+
+require_once $argv[1] . '/arithmetic.php';
+
+use Arithmetic\ArithmeticErrorIntegerOverflow;
+use Arithmetic\InternalException;
+
+use function Arithmetic\{add, div, equal, sub};
+
+assert(add(2, 4) === 6);
+assert(add(4, 8) === 12);
+
+assert(sub(4, 2) === 2);
+try {
+    sub(2, 4);
+    assert(false, 'sub(2, 4) should have overflowed');
+} catch (ArithmeticErrorIntegerOverflow $e) {
+    assert($e->a === 2 && $e->b === 4);
+}
+
+assert(div(8, 4) === 2);
+// A panic becomes an InternalException with its message.
+try {
+    div(8, 0);
+    assert(false, 'div(8, 0) should have panicked');
+} catch (InternalException $e) {
+    assert(\str_contains($e->getMessage(), "Can't divide by zero"));
+}
+
+assert(equal(2, 2));
+assert(!equal(4, 8));
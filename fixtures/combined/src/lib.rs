@@ -2,6 +2,13 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
 
-mod uniffi_fixtures {
+//! Every fixture in one library, for the bindings of components which share it. Each fixture
+//! also builds its own, see `build_bindings.sh`.
+
+mod arithmetic {
+    uniffi_fixture_arithmetic::uniffi_reexport_scaffolding!();
+}
+
+mod loro {
     uniffi_fixture_loro::uniffi_reexport_scaffolding!();
 }
@@ -0,0 +1,12 @@
+<?php
+
+// Every fixture, through the bindings generated from the one library they're all built into,
+// in the directory given as the first argument.
+
+declare(strict_types=1);
+
+require __DIR__ . '/../../arithmetic/tests/arithmetic.php';
+require __DIR__ . '/../../loro/tests/loro.php';
+
+// The components share the library's `\FFI`, rather than each loading their own.
+assert(\Arithmetic\UniffiLib::ffi() === \Loro\UniffiLib::ffi());
@@ -0,0 +1,77 @@
+<?php
+
+// The loro fixture, through the bindings in the directory given as the first argument.
+
+declare(strict_types=1);
+
+require_once $argv[1] . '/loro.php';
+
+use Loro\ContainerId;
+use Loro\ContainerIdLike;
+use Loro\ContainerIdRoot;
+use Loro\ContainerType;
+use Loro\LoroDoc;
+use Loro\LoroValue;
+use Loro\LoroValueLike;
+use Loro\LoroValueMap;
+use Loro\LoroValueString;
+
+/**
+ * A root container of the document, by name. Loro calls back into PHP for its id.
+ */
+final class Root implements ContainerIdLike
+{
+    public function __construct(private string $name)
+    {
+    }
+
+    public function asContainerId(ContainerType $ty): ContainerId
+    {
+        return new ContainerIdRoot($this->name, $ty);
+    }
+}
+
+/**
+ * A value PHP hands Loro as is.
+ */
+final class Value implements LoroValueLike
+{
+    public function __construct(private LoroValue $value)
+    {
+    }
+
+    public function asLoroValue(): LoroValue
+    {
+        return $this->value;
+    }
+}
+
+$doc = new LoroDoc();
+
+$text = $doc->getText(new Root('text'));
+$text->insert(0, 'Hello world!');
+$text->insert(5, ',');
+$doc->commit();
+assert($text->toString() === 'Hello, world!');
+assert($text->lenUnicode() === 13);
+
+$counter = $doc->getCounter(new Root('counter'));
+$counter->increment(2.5);
+$counter->decrement(1.0);
+$doc->commit();
+assert($counter->getValue() === 1.5);
+
+// A snapshot brings another document up to date.
+$copy = new LoroDoc();
+$copy->import($doc->exportSnapshot());
+assert($copy->getText(new Root('text'))->toString() === 'Hello, world!');
+assert($copy->getCounter(new Root('counter'))->getValue() === 1.5);
+
+// Map keys stay strings, even those PHP turns into ints like "1", and what comes back can be
+// written again.
+$map = $doc->getMap(new Root('map'));
+$map->insert('numbers', new Value(new LoroValueMap(['1' => new LoroValueString('one')])));
+$numbers = $map->getValue()->value['numbers'];
+assert($numbers == new LoroValueMap(['1' => new LoroValueString('one')]));
+$map->insert('copy', new Value($numbers));
+assert($map->getValue()->value['copy'] == $numbers);
@@ -0,0 +1,54 @@
+#!/bin/bash
+set -euxo pipefail
+
+# Usage: ./test_bindings.sh [FIXTURE...]
+#
+# Generate each fixture's PHP package on its own with build_bindings.sh, and run the PHP tests
+# of fixtures/<fixture>/tests against it. Without FIXTUREs, that's done for all of them, and
+# then for the combined library, whose tests run every fixture's against the one library.
+#
+# PHP needs ext-ffi, set PHP to use another binary than the php in PATH.
+
+SCRIPT_DIR="${SCRIPT_DIR:-$( cd "$( dirname "${BASH_SOURCE[0]}" )" >/dev/null 2>&1 && pwd )}"
+ROOT_DIR="$SCRIPT_DIR"
+
+BINDINGS_DIR="$ROOT_DIR/out"
+PHP="${PHP:-php}"
+# The tests are plain assertions, which have to be on and throw.
+PHP_FLAGS=(-d ffi.enable=1 -d zend.assertions=1 -d assert.exception=1)
+
+# Run the tests of fixtures/$1 against the bindings in $2.
+run_tests() {
+    local tests=("$ROOT_DIR/fixtures/$1"/tests/*.php)
+    if [[ ! -e "${tests[0]}" ]]; then
+        echo "fixtures/$1 has no tests" >&2
+        exit 1
+    fi
+    for test in "${tests[@]}"; do
+        "$PHP" "${PHP_FLAGS[@]}" "$test" "$2"
+    done
+}
+
+COMBINED=false
+if [[ $# -gt 0 ]]; then
+    FIXTURES=("$@")
+else
+    COMBINED=true
+    FIXTURES=()
+    for dir in "$ROOT_DIR"/fixtures/*/; do
+        fixture="$(basename "$dir")"
+        if [[ "$fixture" != "combined" ]]; then
+            FIXTURES+=("$fixture")
+        fi
+    done
+fi
+
+for fixture in "${FIXTURES[@]}"; do
+    "$ROOT_DIR/build_bindings.sh" "$fixture"
+    run_tests "$fixture" "$BINDINGS_DIR/uniffi_fixture_$fixture"
+done
+
+if $COMBINED; then
+    "$ROOT_DIR/build_bindings.sh" --combined
+    run_tests combined "$BINDINGS_DIR/combined"
+fi